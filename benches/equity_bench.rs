@@ -1,7 +1,7 @@
 use criterion::{criterion_group, criterion_main, Criterion, BenchmarkId};
 use rand::Rng;
 use rand::seq::SliceRandom;
-use poker_wasm::{HoldemRange, OmahaRange, EquityCalculator};
+use poker_wasm::{HoldemRange, OmahaRange, EquityCalculator, generate_hand_ranks};
 use std::hint::black_box;
 use std::collections::HashSet;
 
@@ -56,9 +56,9 @@ fn create_random_omaha_hand(used_cards: &HashSet<u8>, hand_size: usize) -> Optio
 }
 
 fn create_random_omaha_range(num_hands: usize, used_cards: &HashSet<u8>, hand_size: usize) -> OmahaRange {
-    let mut range = OmahaRange::new(hand_size);
     let mut rng = rand::rng();
     let mut added_hands = HashSet::new();
+    let mut hands = Vec::new();
 
     for _ in 0..num_hands {
         if let Some(hand) = create_random_omaha_hand(used_cards, hand_size) {
@@ -66,19 +66,19 @@ fn create_random_omaha_range(num_hands: usize, used_cards: &HashSet<u8>, hand_si
             let hand_key = format!("{:?}", hand);
             if added_hands.insert(hand_key) {
                 let weight: f32 = rng.random_range(0.1..1.0);
-                range.add_hand(&hand, weight);
+                hands.push((hand, weight));
             }
         }
     }
 
-    range
+    OmahaRange::from_hands(hand_size, hands).unwrap()
 }
 
 // --- Hold'em Benchmarks ---
 
 fn bench_holdem_leaf_equity(c: &mut Criterion) {
     let hand_sizes = vec![ 25, 50, 100, 250, 500, 1000];
-    let hand_ranks_data = include_bytes!("../HandRanks.dat").to_vec();
+    let hand_ranks_data = generate_hand_ranks();
     let mut group = c.benchmark_group("holdem_leaf_equity");
 
     for &hand_size in &hand_sizes {
@@ -89,7 +89,30 @@ fn bench_holdem_leaf_equity(c: &mut Criterion) {
 
         group.bench_function(BenchmarkId::from_parameter(hand_size), |b| {
             b.iter(|| {
-                calculator.leaf_equity_vs_range(black_box(&board)).unwrap()
+                calculator.leaf_equity_vs_range(black_box(&board), None).unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_holdem_flop_equity(c: &mut Criterion) {
+    let hand_sizes = vec![100, 500, 1000];
+    let hand_ranks_data = generate_hand_ranks();
+    let mut group = c.benchmark_group("holdem_flop_equity");
+    group.sample_size(10);
+
+    for &hand_size in &hand_sizes {
+        // random ranges have no suit symmetry, so every turn and river is evaluated
+        let board = create_random_board(3);
+        let mut calculator = EquityCalculator::new(hand_ranks_data.clone());
+        calculator.set_hero_range(create_random_range(hand_size, &board));
+        calculator.set_vs_range(create_random_range(hand_size, &board));
+
+        group.bench_function(BenchmarkId::from_parameter(hand_size), |b| {
+            b.iter(|| {
+                calculator.equity_vs_range(black_box(&board), None).unwrap()
             })
         });
     }
@@ -101,7 +124,7 @@ fn bench_holdem_leaf_equity(c: &mut Criterion) {
 
 fn bench_omaha_leaf_equity(c: &mut Criterion) {
     let range_sizes = vec![25, 50, 100, 250];
-    let hand_ranks_data = include_bytes!("../HandRanks.dat").to_vec();
+    let hand_ranks_data = generate_hand_ranks();
     let mut group = c.benchmark_group("omaha_leaf_equity");
 
     for &range_size in &range_sizes {
@@ -119,7 +142,7 @@ fn bench_omaha_leaf_equity(c: &mut Criterion) {
 
         group.bench_function(BenchmarkId::from_parameter(range_size), |b| {
             b.iter(|| {
-                calculator.omaha_leaf_equity_vs_range(black_box(&hero_hand), black_box(&board)).unwrap()
+                calculator.omaha_leaf_equity_vs_range(black_box(&hero_hand), black_box(&board), None).unwrap()
             })
         });
     }
@@ -136,7 +159,7 @@ fn bench_omaha_flop_monte_carlo(c: &mut Criterion) {
         (1000, 100),
     ];
 
-    let hand_ranks_data = include_bytes!("../HandRanks.dat").to_vec();
+    let hand_ranks_data = generate_hand_ranks();
     let mut group = c.benchmark_group("omaha_flop_monte_carlo");
 
     for &(range_size, num_runouts) in &configs {
@@ -159,6 +182,7 @@ fn bench_omaha_flop_monte_carlo(c: &mut Criterion) {
                     black_box(&hero_hand),
                     black_box(&flop),
                     black_box(num_runouts),
+                    None,
                 ).unwrap()
             })
         );
@@ -170,6 +194,7 @@ fn bench_omaha_flop_monte_carlo(c: &mut Criterion) {
 criterion_group!(
     benches,
     bench_holdem_leaf_equity,
+    bench_holdem_flop_equity,
     bench_omaha_leaf_equity,
     bench_omaha_flop_monte_carlo
 );
@@ -1,3 +1,5 @@
+use crate::evaluation::{cards_to_mask, validate_cards};
+
 #[derive(Clone, Copy)]
 pub struct ComboInfo {
     pub p: i32,
@@ -6,3 +8,13 @@ pub struct ComboInfo {
     pub vs_weight: f32,
     pub combo: [u8; 2],
 }
+
+/// Mask of cards known to be out of the deck (folded, burned or exposed)
+/// Fails on invalid or repeated cards and on dead cards that are on the board
+pub(crate) fn dead_card_mask(dead_cards: &[u8], board: &[u8]) -> Result<u64, String> {
+    let dead_mask = validate_cards(dead_cards)?;
+    if dead_mask & cards_to_mask(board) != 0 {
+        return Err("Dead cards can't be on the board".to_string());
+    }
+    Ok(dead_mask)
+}
@@ -1,19 +1,25 @@
-use crate::evaluation::{gen_board_eval, IDX2HAND};
-use super::blocker::ComboInfo;
+use crate::evaluation::{
+    cards_to_mask, permute_suit, validate_cards, Board, Card, EquityError, HandEvaluator, HoldemHand, FULL_DECK_MASK, IDX2HAND,
+};
+use super::blocker::{dead_card_mask, ComboInfo};
+use super::preflop::calculate_preflop_equity_vs_range;
+use super::runouts::{board_runouts, holdem_symmetries, runout_classes};
 
 use crate::{Equity, EquityResult, HoldemRange};
 
-pub fn hand_leaf_equity_vs_range(
-    hand_ranks_data: &[u8],
+/// Villain combos holding a board or dead card are skipped
+pub fn hand_leaf_equity_vs_range<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
     hand: &[u8; 2],
     vs_range: &HoldemRange,
     board: &[u8],
+    dead_cards: &[u8],
 ) -> Equity {
     assert!(board.len() >= 3 && board.len() <= 5, "board must be 3-5 cards");
 
-    let board_eval = gen_board_eval(hand_ranks_data, board);
+    let board_eval = evaluator.board_eval(board);
 
-    let mut board_mask = 0u64;
+    let mut board_mask = cards_to_mask(dead_cards);
     for &card in board {
         board_mask |= 1u64 << card;
     }
@@ -48,216 +54,301 @@ pub fn hand_leaf_equity_vs_range(
     }
 }
 
-pub fn calculate_leaf_equity(
-    hand_ranks_data: &[u8],
+/// Equity of every hero combo vs the villain range on a 3-5 card board
+/// Combos of either range holding a board or dead card are skipped
+/// Fails on invalid or repeated cards and on dead cards that are on the board
+pub fn calculate_leaf_equity<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
     hero_range: &HoldemRange,
     vs_range: &HoldemRange,
     board: &[u8],
+    dead_cards: &[u8],
+) -> Result<Vec<EquityResult>, String> {
+    if board.len() < 3 || board.len() > 5 {
+        return Err("Board must have 3, 4, or 5 cards".to_string());
+    }
+    validate_cards(board)?;
+    let dead_mask = dead_card_mask(dead_cards, board)?;
+    Ok(leaf_equity_with_dead_mask(evaluator, hero_range, vs_range, board, dead_mask))
+}
+
+pub(crate) fn leaf_equity_with_dead_mask<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
+    hero_range: &HoldemRange,
+    vs_range: &HoldemRange,
+    board: &[u8],
+    dead_mask: u64,
 ) -> Vec<EquityResult> {
-    assert!(board.len() >= 3 && board.len() <= 5, "board must be 3-5 cards");
+    LeafEquityWorkspace::new(hero_range, vs_range, dead_mask | cards_to_mask(board))
+        .leaf_equity(evaluator, board)
+        .to_vec()
+}
 
-    let board_eval = gen_board_eval(hand_ranks_data, board);
+/// Reusable state for leaf equity on many runouts of one board, e.g. every turn and river of a flop
+///
+/// Combos with hero or villain weight that aren't blocked by the shared cards are collected once,
+/// and each runout only drops the combos its own cards block. Buffers are kept between runouts, so
+/// evaluating a runout doesn't allocate. Card removal uses per card running sums of villain weight
+/// instead of a per combo prefix sum matrix
+pub struct LeafEquityWorkspace {
+    candidates: Vec<ComboInfo>,
+    // live combos of the current runout as (order preserving rank << 16) | candidate index
+    order: Vec<u64>,
+    sort_buffer: Vec<u64>,
+    results: Vec<EquityResult>,
+}
 
-    let mut board_mask = 0u64;
-    for &card in board {
-        board_mask |= 1u64 << card;
+/// Sorts (rank << 16) | index keys by rank with an LSD radix sort over the 4 rank bytes
+/// Bytes that every key shares are skipped, usually leaving 2 passes
+fn radix_sort_by_rank(keys: &mut Vec<u64>, buffer: &mut Vec<u64>) {
+    // the histograms cost more than a comparison sort on small ranges
+    if keys.len() < 256 {
+        keys.sort_unstable();
+        return;
     }
 
-    let mut all_combos: Vec<ComboInfo> = Vec::with_capacity(1326);
-    for (idx, &combo) in IDX2HAND.iter().enumerate() {
-        if (board_mask & (1u64 << combo[0]) != 0) || (board_mask & (1u64 << combo[1]) != 0) {
-            continue;
+    let mut counts = [[0usize; 256]; 4];
+    for &key in keys.iter() {
+        for (byte, count) in counts.iter_mut().enumerate() {
+            count[(key >> (16 + 8 * byte)) as usize & 0xff] += 1;
         }
+    }
 
-        let vs_weight: f32 = vs_range.range[idx];
-        let self_weight = hero_range.range[idx];
-
-        if vs_weight > 0.0 || self_weight > 0.0 {
-            all_combos.push(ComboInfo {
-                p: board_eval(&combo),
-                idx: idx as u16,
-                self_weight,
-                vs_weight,
-                combo,
-            });
+    buffer.resize(keys.len(), 0);
+    for (byte, count) in counts.iter().enumerate() {
+        if count.contains(&keys.len()) {
+            continue;
         }
+        let mut offsets = [0usize; 256];
+        let mut offset = 0;
+        for (slot, &bucket_count) in offsets.iter_mut().zip(count) {
+            *slot = offset;
+            offset += bucket_count;
+        }
+        for &key in keys.iter() {
+            let bucket = (key >> (16 + 8 * byte)) as usize & 0xff;
+            buffer[offsets[bucket]] = key;
+            offsets[bucket] += 1;
+        }
+        std::mem::swap(keys, buffer);
     }
+}
 
-    all_combos.sort_unstable_by_key(|a| a.p);
-
-    let n_combos: usize = all_combos.len();
-    let mut total_weight = 0.0;
-    let mut weight_prefix = Vec::with_capacity(n_combos);
-
-    let mut cur_p = -1;
-    let mut rank_ranges = Vec::new();
-    let mut cur_p_idx_range: (usize, usize) = (0, 0);
-    let mut cur_rank_idx = -1;
-    let mut idx_to_range_idx: [usize; 1326] = [0; 1326];
-
-    for (i, combo_info) in all_combos.iter().enumerate() {
-        total_weight += combo_info.vs_weight;
-        weight_prefix.push(total_weight);
-
-        if combo_info.p != cur_p {
-            cur_rank_idx += 1; // on first iter cur_rank_idx becomes 0
-            cur_p = combo_info.p;
-            cur_p_idx_range = (i, i);
-            rank_ranges.push((i, i));
-        } else {
-            cur_p_idx_range.1 = i;
-            rank_ranges[cur_rank_idx as usize].1 = i;
+impl LeafEquityWorkspace {
+    /// `blocked_mask` holds the cards shared by every runout (the board so far and dead cards)
+    /// Combos holding one of them are skipped
+    pub fn new(hero_range: &HoldemRange, vs_range: &HoldemRange, blocked_mask: u64) -> Self {
+        let mut candidates = Vec::with_capacity(1326);
+        for (idx, &combo) in IDX2HAND.iter().enumerate() {
+            let (self_weight, vs_weight) = (hero_range.range[idx], vs_range.range[idx]);
+            if cards_to_mask(&combo) & blocked_mask == 0 && (self_weight > 0.0 || vs_weight > 0.0) {
+                candidates.push(ComboInfo { p: 0, idx: idx as u16, self_weight, vs_weight, combo });
+            }
         }
 
-        idx_to_range_idx[combo_info.idx as usize] = cur_rank_idx as usize;
-    }
-
-    let mut blocked_prefix = vec![0.0; n_combos * 52];
-
-    if n_combos > 0 {
-        // handle i=0 case
-        let combo_info = &all_combos[0];
-        let c1 = combo_info.combo[0] as usize;
-        let c2 = combo_info.combo[1] as usize;
-        blocked_prefix[c1] += combo_info.vs_weight;
-        blocked_prefix[c2] += combo_info.vs_weight;
-
-        for (i, combo_info) in all_combos.iter().enumerate().skip(1) {
-            let prev_slice_start = (i - 1) * 52;
-            let curr_slice_start = i * 52;
-
-            // copy previous cumulative sums
-            blocked_prefix
-                .copy_within(prev_slice_start..prev_slice_start + 52, curr_slice_start);
-
-            // add current combo's weight
-            let c1 = combo_info.combo[0] as usize;
-            let c2 = combo_info.combo[1] as usize;
-            blocked_prefix[curr_slice_start + c1] += combo_info.vs_weight;
-            blocked_prefix[curr_slice_start + c2] += combo_info.vs_weight;
+        LeafEquityWorkspace {
+            order: Vec::with_capacity(candidates.len()),
+            sort_buffer: Vec::with_capacity(candidates.len()),
+            results: Vec::with_capacity(candidates.len()),
+            candidates,
         }
     }
 
-    let mut result = Vec::with_capacity(hero_range.range.iter().filter(|&&w| w > 0.0).count());
-    for combo_info in all_combos.iter() {
-        if combo_info.self_weight == 0.0 {
-            continue;
+    /// Equity of every hero combo vs the villain range on a 3-5 card board, from the weakest hand
+    /// to the strongest. Combos holding a board card are skipped
+    pub fn leaf_equity<E: HandEvaluator + ?Sized>(&mut self, evaluator: &E, board: &[u8]) -> &[EquityResult] {
+        assert!(board.len() >= 3 && board.len() <= 5, "board must be 3-5 cards");
+
+        let board_eval = evaluator.board_eval(board);
+        let board_mask = cards_to_mask(board);
+
+        // villain weight of the live combos holding each card
+        let mut card_weights = [0.0f32; 52];
+        let mut total_weight = 0.0f32;
+        self.order.clear();
+        for (candidate_idx, info) in self.candidates.iter_mut().enumerate() {
+            let [c1, c2] = info.combo;
+            if cards_to_mask(&info.combo) & board_mask != 0 {
+                continue;
+            }
+            info.p = board_eval(&info.combo);
+            // flipping the sign bit keeps the rank order in the unsigned key
+            let rank_key = (info.p as u32 ^ 0x8000_0000) as u64;
+            self.order.push(rank_key << 16 | candidate_idx as u64);
+
+            total_weight += info.vs_weight;
+            card_weights[c1 as usize] += info.vs_weight;
+            card_weights[c2 as usize] += info.vs_weight;
         }
+        radix_sort_by_rank(&mut self.order, &mut self.sort_buffer);
+
+        // villain weight ranked below the current group, in total and per card
+        let mut below_weights = [0.0f32; 52];
+        let mut below_weight = 0.0f32;
+        let candidate = |key: u64| &self.candidates[(key & 0xffff) as usize];
+        self.results.clear();
+
+        let mut start = 0;
+        while start < self.order.len() {
+            let rank_key = self.order[start] >> 16;
+            let len = self.order[start..].iter().take_while(|&&key| key >> 16 == rank_key).count();
+            let group = &self.order[start..start + len];
+            let first_result = self.results.len();
+
+            // beaten: everything below minus combos sharing a card with hero's
+            for info in group.iter().map(|&key| candidate(key)).filter(|info| info.self_weight > 0.0) {
+                let [c1, c2] = info.combo.map(usize::from);
+                self.results.push(EquityResult {
+                    combo: info.combo,
+                    hand_idx: info.idx as usize,
+                    equity: Equity {
+                        win: below_weight - below_weights[c1] - below_weights[c2],
+                        tie: 0.0,
+                        lose: 0.0,
+                    },
+                });
+            }
 
-        let combo = combo_info.combo;
-        let rank_idx = idx_to_range_idx[combo_info.idx as usize];
-        let (idx_range_start, idx_range_end) = rank_ranges[rank_idx];
-
-        let mut beat_weight = if idx_range_start > 0 {
-            weight_prefix[idx_range_start - 1]
-        } else {
-            0.0
-        };
-        let mut tie_weight =
-            weight_prefix[idx_range_end] - beat_weight;
-        let mut after_blocker_weight = total_weight;
-
-        for &card in &combo {
-            let card_usize = card as usize;
-            if n_combos > 0 {
-                let blocked_weight = blocked_prefix[(n_combos - 1) * 52 + card_usize];
-
-                let blocked_beat_weight = if idx_range_start > 0 {
-                    blocked_prefix[(idx_range_start - 1) * 52 + card_usize]
-                } else {
-                    0.0
-                };
-                let blocked_tie_weight = blocked_prefix[idx_range_end * 52 + card_usize] - if idx_range_start > 0 {
-                    blocked_prefix[(idx_range_start - 1) * 52 + card_usize]
-                } else {
-                    0.0
-                };
+            for info in group.iter().map(|&key| candidate(key)) {
+                below_weight += info.vs_weight;
+                below_weights[info.combo[0] as usize] += info.vs_weight;
+                below_weights[info.combo[1] as usize] += info.vs_weight;
+            }
 
-                after_blocker_weight -= blocked_weight;
-                beat_weight -= blocked_beat_weight;
-                tie_weight -= blocked_tie_weight;
+            // hero's own combo holds both cards so it was taken off twice, add it back once
+            let heroes = group.iter().map(|&key| candidate(key)).filter(|info| info.self_weight > 0.0);
+            for (result, info) in self.results[first_result..].iter_mut().zip(heroes) {
+                let [c1, c2] = info.combo.map(usize::from);
+                let not_beaten = below_weight - below_weights[c1] - below_weights[c2] + info.vs_weight;
+                let live = total_weight - card_weights[c1] - card_weights[c2] + info.vs_weight;
+                result.equity.tie = not_beaten - result.equity.win;
+                result.equity.lose = live - not_beaten;
             }
 
+            start += len;
         }
 
-        let double_blocked_weight = if n_combos > 0 {
-            combo_info.vs_weight
-        } else {
-            0.0
-        };
-        after_blocker_weight += double_blocked_weight;
-        tie_weight += double_blocked_weight;
-
-        let lose_weight = after_blocker_weight - beat_weight - tie_weight;
-
-        result.push(EquityResult {
-            combo,
-            hand_idx: combo_info.idx as usize,
-            equity: Equity {
-                win: beat_weight,
-                tie: tie_weight,
-                lose: lose_weight,
-            },
-        });
+        &self.results
     }
+}
 
-    result
+/// Copy of the range without the combos holding a dead card
+fn without_dead_combos(range: &HoldemRange, dead_mask: u64) -> HoldemRange {
+    let mut live = range.clone();
+    range.for_each_weighted(|_weight, idx| {
+        if cards_to_mask(&IDX2HAND[idx]) & dead_mask != 0 {
+            live.set(idx, 0.0);
+        }
+    });
+    live
 }
 
 /// Calculate equity with board enumeration (3, 4, or 5-card boards)
-pub fn calculate_equity_vs_range(
-    hand_ranks_data: &[u8],
+/// An empty board gives exact preflop equity from the precomputed matchup table
+/// Dead cards never come on the turn or river and block combos holding them, preflop they only
+/// block combos since the table deals boards from the rest of the deck
+pub fn calculate_equity_vs_range<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
     hero_range: &HoldemRange,
     vs_range: &HoldemRange,
     board: &[u8],
+    dead_cards: &[u8],
 ) -> Result<Vec<EquityResult>, String> {
-    if board.len() < 3 || board.len() > 5 {
-        return Err("Board must have 3, 4, or 5 cards".to_string());
+    if !board.is_empty() && (board.len() < 3 || board.len() > 5) {
+        return Err("Board must be empty (preflop) or have 3, 4, or 5 cards".to_string());
+    }
+    let dead_mask = dead_card_mask(dead_cards, board)?;
+
+    if board.is_empty() {
+        if dead_mask == 0 {
+            return Ok(calculate_preflop_equity_vs_range(hero_range, vs_range));
+        }
+        return Ok(calculate_preflop_equity_vs_range(
+            &without_dead_combos(hero_range, dead_mask),
+            &without_dead_combos(vs_range, dead_mask),
+        ));
     }
 
     if board.len() == 5 {
-        return Ok(calculate_leaf_equity(hand_ranks_data, hero_range, vs_range, board));
+        return Ok(leaf_equity_with_dead_mask(evaluator, hero_range, vs_range, board, dead_mask));
     }
 
-    let mut aggregated_equities = vec![Equity::default(); 1326];
+    Ok(enumerate_equity_vs_range(evaluator, hero_range, vs_range, board, FULL_DECK_MASK & !dead_mask))
+}
 
-    if board.len() == 3 {
-        let mut board_mask = 0u64;
-        for &c in board { board_mask |= 1u64 << c; }
-
-        for turn in 0..52 {
-            if (board_mask & (1u64 << turn)) != 0 { continue; }
-            let turn_mask = board_mask | (1u64 << turn);
-            for river in (turn + 1)..52 {
-                if (turn_mask & (1u64 << river)) != 0 { continue; }
-
-                let full_board = [board[0], board[1], board[2], turn, river];
-                let equity_results = calculate_leaf_equity(hand_ranks_data, hero_range, vs_range, &full_board);
-                for result in equity_results {
-                    aggregated_equities[result.hand_idx].win += result.equity.win;
-                    aggregated_equities[result.hand_idx].tie += result.equity.tie;
-                    aggregated_equities[result.hand_idx].lose += result.equity.lose;
-                }
-            }
-        }
-    } else if board.len() == 4 {
-        let mut board_mask = 0u64;
-        for &c in board { board_mask |= 1u64 << c; }
+/// calculate_equity_vs_range on a validated board, fails if a dead card is on the board
+pub fn calculate_equity_vs_range_typed<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
+    hero_range: &HoldemRange,
+    vs_range: &HoldemRange,
+    board: &Board,
+    dead_cards: &[Card],
+) -> Result<Vec<EquityResult>, EquityError> {
+    let dead_cards: Vec<u8> = dead_cards.iter().map(|&card| card.into()).collect();
+    calculate_equity_vs_range(evaluator, hero_range, vs_range, board.as_bytes(), &dead_cards).map_err(EquityError::Invalid)
+}
 
-        for river in 0..52 {
-            if (board_mask & (1u64 << river)) != 0 { continue; }
+/// calculate_leaf_equity on a validated board, fails if a dead card is on the board
+pub fn calculate_leaf_equity_typed<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
+    hero_range: &HoldemRange,
+    vs_range: &HoldemRange,
+    board: &Board,
+    dead_cards: &[Card],
+) -> Result<Vec<EquityResult>, EquityError> {
+    let dead_cards: Vec<u8> = dead_cards.iter().map(|&card| card.into()).collect();
+    calculate_leaf_equity(evaluator, hero_range, vs_range, board.as_bytes(), &dead_cards).map_err(EquityError::Invalid)
+}
+
+/// hand_leaf_equity_vs_range with validated cards, fails if the hand or a dead card is on the
+/// board or the hand holds a dead card
+pub fn hand_leaf_equity_vs_range_typed<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
+    hand: &HoldemHand,
+    vs_range: &HoldemRange,
+    board: &Board,
+    dead_cards: &[Card],
+) -> Result<Equity, EquityError> {
+    board.check_hand(hand.as_bytes())?;
+    let dead_cards: Vec<u8> = dead_cards.iter().map(|&card| card.into()).collect();
+    if dead_card_mask(&dead_cards, board.as_bytes())? & hand.mask() != 0 {
+        return Err(EquityError::Invalid("Hand holds a dead card".to_string()));
+    }
+    Ok(hand_leaf_equity_vs_range(evaluator, hand.as_bytes(), vs_range, board.as_bytes(), &dead_cards))
+}
 
-            let full_board = [board[0], board[1], board[2], board[3], river];
-            let equity_results = calculate_leaf_equity(hand_ranks_data, hero_range, vs_range, &full_board);
+/// Sums leaf equity over every turn/river runout drawn from the cards in `deck_mask`
+/// Combos holding a card outside the deck are skipped. Runouts that are suit relabelings of each
+/// other are evaluated once, with the results moved to the relabeled combos
+pub(crate) fn enumerate_equity_vs_range<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
+    hero_range: &HoldemRange,
+    vs_range: &HoldemRange,
+    board: &[u8],
+    deck_mask: u64,
+) -> Vec<EquityResult> {
+    let mut aggregated_equities = vec![Equity::default(); 1326];
+    let dead_mask = FULL_DECK_MASK & !deck_mask;
+
+    let runouts = board_runouts(board, cards_to_mask(board) | dead_mask);
+    let symmetries = holdem_symmetries(hero_range, vs_range, board, dead_mask);
+    let mut workspace = LeafEquityWorkspace::new(hero_range, vs_range, cards_to_mask(board) | dead_mask);
+    for class in runout_classes(&runouts, &symmetries) {
+        let equity_results = workspace.leaf_equity(evaluator, &runouts[class.representative]);
+        for &(runout_idx, perm) in &class.members {
             for result in equity_results {
-                aggregated_equities[result.hand_idx].win += result.equity.win;
-                aggregated_equities[result.hand_idx].tie += result.equity.tie;
-                aggregated_equities[result.hand_idx].lose += result.equity.lose;
+                let hand_idx = if runout_idx == class.representative {
+                    result.hand_idx
+                } else {
+                    HoldemRange::get_hand_idx(result.combo.map(|card| permute_suit(card, &perm)))
+                };
+                aggregated_equities[hand_idx].win += result.equity.win;
+                aggregated_equities[hand_idx].tie += result.equity.tie;
+                aggregated_equities[hand_idx].lose += result.equity.lose;
             }
         }
     }
 
-
     let mut final_results = Vec::new();
     hero_range.for_each_weighted(|_weight, hand_idx| {
         final_results.push(EquityResult {
@@ -267,5 +358,5 @@ pub fn calculate_equity_vs_range(
         });
     });
 
-    Ok(final_results)
+    final_results
 }
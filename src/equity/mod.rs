@@ -1,6 +1,13 @@
 pub mod holdem;
 pub mod blocker;
+pub mod multiway;
 pub mod omaha;
+pub mod omaha_hilo;
+pub mod preflop;
+mod runouts;
+pub mod short_deck;
 
 pub use blocker::ComboInfo;
+pub use multiway::MultiwayEquityResult;
 pub use omaha::RunoutEquities;
+pub use omaha_hilo::{HiLoEquity, RunoutHiLoEquities};
@@ -0,0 +1,391 @@
+// Hold'em equity for three or more players, each holding a combo from their own range
+//
+// A deal gives every player a combo, no two sharing a card or touching the board and dead cards,
+// and is as likely as the product of the combo weights. On each runout the players with the best
+// hand split the pot evenly, so a combo's equity is its expected pot share over the deals and
+// runouts where it's dealt. Small spots enumerate every deal on every runout; larger ones sample
+// a fixed number of deals per combo, so every combo gets an estimate of its own
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use wasm_bindgen::prelude::*;
+
+use crate::evaluation::{cards_to_mask, validate_cards, HandEvaluator};
+use crate::HoldemRange;
+
+use super::blocker::dead_card_mask;
+
+/// Most ranges a multiway calculation takes
+pub const MAX_MULTIWAY_PLAYERS: usize = 9;
+
+/// Largest runouts * (live combos + deals) that's enumerated exactly, bigger spots are sampled
+pub const MULTIWAY_EXACT_LIMIT: u64 = 20_000_000;
+
+const MULTIWAY_SEED: u64 = 0x6d75_6c74_6977_6179;
+/// Tries to deal the other players non-overlapping combos before a sample is dropped
+const DEAL_ATTEMPTS: usize = 1000;
+
+/// Multiway result for one combo of one player
+/// win and tie are the chances of taking the whole pot and of splitting it, equity is the expected
+/// share of the pot with split pots divided between the tied players
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MultiwayEquityResult {
+    pub(crate) player: usize,
+    pub(crate) combo: [u8; 2],
+    pub(crate) hand_idx: usize,
+    pub(crate) win: f32,
+    pub(crate) tie: f32,
+    pub(crate) equity: f32,
+}
+
+#[wasm_bindgen]
+impl MultiwayEquityResult {
+    /// Index of the player's range in the input
+    #[wasm_bindgen(getter)]
+    pub fn player(&self) -> usize {
+        self.player
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn combo(&self) -> Vec<u8> {
+        self.combo.to_vec()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn hand_idx(&self) -> usize {
+        self.hand_idx
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn win(&self) -> f32 {
+        self.win
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn tie(&self) -> f32 {
+        self.tie
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn equity(&self) -> f32 {
+        self.equity
+    }
+}
+
+/// Live combos of one player as (hand idx, card mask), with cumulative weights for sampling
+struct Seat {
+    combos: Vec<(usize, u64)>,
+    weights: Vec<f64>,
+    cumulative: Vec<f64>,
+}
+
+impl Seat {
+    fn new(range: &HoldemRange, blocked: u64) -> Self {
+        let mut seat = Seat { combos: Vec::new(), weights: Vec::new(), cumulative: Vec::new() };
+        let mut total = 0.0;
+        range.for_each_weighted(|weight, idx| {
+            let mask = cards_to_mask(&HoldemRange::from_hand_idx(idx));
+            if mask & blocked == 0 {
+                total += weight as f64;
+                seat.combos.push((idx, mask));
+                seat.weights.push(weight as f64);
+                seat.cumulative.push(total);
+            }
+        });
+        seat
+    }
+
+    /// Position of a weighted random combo
+    fn sample(&self, rng: &mut StdRng) -> usize {
+        let target = rng.random::<f64>() * self.cumulative[self.cumulative.len() - 1];
+        self.cumulative.partition_point(|&cum| cum <= target).min(self.combos.len() - 1)
+    }
+}
+
+/// Weight of the deals and runouts seen by a combo and how much of it went to wins, ties and
+/// pot share
+#[derive(Clone, Copy, Default)]
+struct Tally {
+    weight: f64,
+    win: f64,
+    tie: f64,
+    share: f64,
+}
+
+impl Tally {
+    fn add(&mut self, weight: f64, rank: i32, best: i32, tied: usize) {
+        self.weight += weight;
+        if rank == best {
+            if tied == 1 {
+                self.win += weight;
+            } else {
+                self.tie += weight;
+            }
+            self.share += weight / tied as f64;
+        }
+    }
+}
+
+/// Best rank among `ranks` and how many players have it
+fn showdown(ranks: &[i32]) -> (i32, usize) {
+    let best = ranks.iter().copied().max().unwrap_or(i32::MIN);
+    (best, ranks.iter().filter(|&&rank| rank == best).count())
+}
+
+/// Validated live combos of every player
+fn seats(ranges: &[HoldemRange], board: &[u8], dead_cards: &[u8]) -> Result<(Vec<Seat>, u64), String> {
+    if ranges.len() < 2 || ranges.len() > MAX_MULTIWAY_PLAYERS {
+        return Err(format!("Multiway equity takes 2 to {MAX_MULTIWAY_PLAYERS} ranges, got {}", ranges.len()));
+    }
+    if !matches!(board.len(), 0 | 3 | 4 | 5) {
+        return Err("Board must be empty (preflop) or have 3, 4, or 5 cards".to_string());
+    }
+    let blocked = validate_cards(board)? | dead_card_mask(dead_cards, board)?;
+    if (52 - blocked.count_ones() as usize) < 2 * ranges.len() + 5 - board.len() {
+        return Err("Not enough cards left to deal every player and the rest of the board".to_string());
+    }
+
+    let seats: Vec<Seat> = ranges.iter().map(|range| Seat::new(range, blocked)).collect();
+    if let Some(player) = seats.iter().position(|seat| seat.combos.is_empty()) {
+        return Err(format!("Range {player} has no combos left after the board and dead cards"));
+    }
+    Ok((seats, blocked))
+}
+
+/// Results of every combo that was dealt, by player and then hand idx
+fn collect_results(seats: &[Seat], tallies: &[Vec<Tally>]) -> Result<Vec<MultiwayEquityResult>, String> {
+    let mut results = Vec::new();
+    for (player, (seat, tallies)) in seats.iter().zip(tallies).enumerate() {
+        for (&(hand_idx, _), tally) in seat.combos.iter().zip(tallies) {
+            if tally.weight > 0.0 {
+                results.push(MultiwayEquityResult {
+                    player,
+                    combo: HoldemRange::from_hand_idx(hand_idx),
+                    hand_idx,
+                    win: (tally.win / tally.weight) as f32,
+                    tie: (tally.tie / tally.weight) as f32,
+                    equity: (tally.share / tally.weight) as f32,
+                });
+            }
+        }
+    }
+    if results.is_empty() {
+        return Err("No deal gives every player a combo without sharing cards".to_string());
+    }
+    Ok(results)
+}
+
+/// Number of ways to pick k of n cards
+fn choose(n: usize, k: usize) -> u64 {
+    (0..k).fold(1u64, |ways, i| ways * (n - i) as u64 / (i as u64 + 1))
+}
+
+/// Calls `f` with every complete board made from `board` and cards of `deck`, plus the mask of
+/// the cards that were added
+fn for_each_runout(board: &[u8], deck: &[u8], mut f: impl FnMut(&[u8; 5], u64)) {
+    let missing = 5 - board.len();
+    if deck.len() < missing {
+        return;
+    }
+    let mut full = [0u8; 5];
+    full[..board.len()].copy_from_slice(board);
+    let mut picks: Vec<usize> = (0..missing).collect();
+
+    loop {
+        let mut mask = 0u64;
+        for (slot, &pick) in full[board.len()..].iter_mut().zip(&picks) {
+            *slot = deck[pick];
+            mask |= 1u64 << deck[pick];
+        }
+        f(&full, mask);
+
+        // next combination of positions in the deck
+        let Some(slot) = (0..missing).rev().find(|&slot| picks[slot] < deck.len() - missing + slot) else {
+            return;
+        };
+        picks[slot] += 1;
+        for next in slot + 1..missing {
+            picks[next] = picks[next - 1] + 1;
+        }
+    }
+}
+
+/// Calls `f` with every deal of one combo per player where no combo holds a card of `used` or
+/// shares one with another, as combo positions and the product of their weights
+/// Deals are walked depth first rather than stored, so memory doesn't grow with their number
+fn for_each_deal(seats: &[Seat], used: u64, mut f: impl FnMut(&[usize], f64)) {
+    let mut current = vec![0usize; seats.len()];
+    let mut stack = vec![(0usize, 0usize, used, 1.0f64)];
+    while let Some((player, start, used, weight)) = stack.pop() {
+        let seat = &seats[player];
+        let Some(offset) = seat.combos[start..].iter().position(|&(_, mask)| mask & used == 0) else {
+            continue;
+        };
+        let pos = start + offset;
+        // come back for the next combo of this player once the rest of this deal is done
+        stack.push((player, pos + 1, used, weight));
+        current[player] = pos;
+        let (mask, weight) = (used | seat.combos[pos].1, weight * seat.weights[pos]);
+        if player + 1 == seats.len() {
+            f(&current, weight);
+        } else {
+            stack.push((player + 1, 0, mask, weight));
+        }
+    }
+}
+
+/// Multiway equity of every live combo in each range, ranges[0] is usually hero
+/// Enumerates exactly when runouts * (live combos + deals) is at most MULTIWAY_EXACT_LIMIT and
+/// samples `trials_per_combo` deals per combo otherwise
+/// Combos that can't be dealt alongside the other ranges are left out
+pub fn calculate_multiway_equity<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
+    ranges: &[HoldemRange],
+    board: &[u8],
+    dead_cards: &[u8],
+    trials_per_combo: usize,
+) -> Result<Vec<MultiwayEquityResult>, String> {
+    let (seats, blocked) = seats(ranges, board, dead_cards)?;
+    let deck_size = 52 - blocked.count_ones() as usize;
+    let runouts = choose(deck_size, 5 - board.len());
+    let live_combos: u64 = seats.iter().map(|seat| seat.combos.len() as u64).sum();
+    // an upper bound on the deals, overlapping combos are only dropped while enumerating
+    let deals = seats.iter().fold(1u64, |deals, seat| deals.saturating_mul(seat.combos.len() as u64));
+
+    if runouts.saturating_mul(live_combos.saturating_add(deals)) <= MULTIWAY_EXACT_LIMIT {
+        calculate_multiway_equity_exact(evaluator, ranges, board, dead_cards)
+    } else {
+        calculate_multiway_equity_monte_carlo(evaluator, ranges, board, dead_cards, trials_per_combo)
+    }
+}
+
+/// Exact multiway equity over every deal of combos and every runout
+/// The work grows with the product of the range sizes, see calculate_multiway_equity
+pub fn calculate_multiway_equity_exact<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
+    ranges: &[HoldemRange],
+    board: &[u8],
+    dead_cards: &[u8],
+) -> Result<Vec<MultiwayEquityResult>, String> {
+    let (seats, blocked) = seats(ranges, board, dead_cards)?;
+    let players = seats.len();
+
+    // hand idx of every live combo once, ranked on each runout
+    let mut live = [false; 1326];
+    for seat in &seats {
+        for &(hand_idx, _) in &seat.combos {
+            live[hand_idx] = true;
+        }
+    }
+    let live: Vec<usize> = (0..1326).filter(|&idx| live[idx]).collect();
+
+    let deck: Vec<u8> = (0..52).filter(|&card| blocked & (1u64 << card) == 0).collect();
+    let mut tallies: Vec<Vec<Tally>> = seats.iter().map(|seat| vec![Tally::default(); seat.combos.len()]).collect();
+    let mut combo_ranks = [0i32; 1326];
+    let mut ranks = vec![0i32; players];
+    for_each_runout(board, &deck, |full_board, runout_mask| {
+        let board_eval = evaluator.board_eval(full_board);
+        for &idx in &live {
+            let combo = HoldemRange::from_hand_idx(idx);
+            if cards_to_mask(&combo) & runout_mask == 0 {
+                combo_ranks[idx] = board_eval(&combo);
+            }
+        }
+
+        for_each_deal(&seats, runout_mask, |deal, weight| {
+            for ((rank, seat), &pos) in ranks.iter_mut().zip(&seats).zip(deal) {
+                *rank = combo_ranks[seat.combos[pos].0];
+            }
+            let (best, tied) = showdown(&ranks);
+            for ((player_tallies, &rank), &pos) in tallies.iter_mut().zip(&ranks).zip(deal) {
+                player_tallies[pos].add(weight, rank, best, tied);
+            }
+        });
+    });
+
+    collect_results(&seats, &tallies)
+}
+
+/// Sampled multiway equity, dealing `trials_per_combo` random deals and runouts to each combo
+/// The other players' combos are drawn by weight and redrawn together until none overlap, so a
+/// sample follows the same card removal as exact enumeration. Uses a fixed seed
+pub fn calculate_multiway_equity_monte_carlo<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
+    ranges: &[HoldemRange],
+    board: &[u8],
+    dead_cards: &[u8],
+    trials_per_combo: usize,
+) -> Result<Vec<MultiwayEquityResult>, String> {
+    if trials_per_combo == 0 {
+        return Err("Monte Carlo needs at least one trial per combo".to_string());
+    }
+    let (seats, blocked) = seats(ranges, board, dead_cards)?;
+    let players = seats.len();
+    let mut rng = StdRng::seed_from_u64(MULTIWAY_SEED);
+
+    let mut tallies: Vec<Vec<Tally>> = seats.iter().map(|seat| vec![Tally::default(); seat.combos.len()]).collect();
+    let mut dealt = vec![0usize; players];
+    let mut ranks = vec![0i32; players];
+    let mut full_board = [0u8; 5];
+    full_board[..board.len()].copy_from_slice(board);
+
+    for (player, seat) in seats.iter().enumerate() {
+        for (pos, &(_, hero_mask)) in seat.combos.iter().enumerate() {
+            dealt[player] = pos;
+            for _ in 0..trials_per_combo {
+                let Some(used) = deal_others(&mut rng, &seats, player, &mut dealt, blocked | hero_mask) else {
+                    continue;
+                };
+                deal_runout(&mut rng, used, &mut full_board[board.len()..]);
+
+                let board_eval = evaluator.board_eval(&full_board);
+                for ((rank, seat), &pos) in ranks.iter_mut().zip(&seats).zip(&dealt) {
+                    *rank = board_eval(&HoldemRange::from_hand_idx(seat.combos[pos].0));
+                }
+                let (best, tied) = showdown(&ranks);
+                tallies[player][pos].add(1.0, ranks[player], best, tied);
+            }
+        }
+    }
+
+    collect_results(&seats, &tallies)
+}
+
+/// Draws a combo for every player but `hero` into `dealt`, retrying until none overlap `used` or
+/// each other. Returns the cards in use, None if no attempt fits
+fn deal_others(rng: &mut StdRng, seats: &[Seat], hero: usize, dealt: &mut [usize], used: u64) -> Option<u64> {
+    'attempt: for _ in 0..DEAL_ATTEMPTS {
+        let mut mask = used;
+        for (player, seat) in seats.iter().enumerate() {
+            if player == hero {
+                continue;
+            }
+            let pos = seat.sample(rng);
+            let combo_mask = seat.combos[pos].1;
+            if combo_mask & mask != 0 {
+                continue 'attempt;
+            }
+            mask |= combo_mask;
+            dealt[player] = pos;
+        }
+        return Some(mask);
+    }
+    None
+}
+
+/// Fills `cards` with random cards not in `used`, which must leave at least that many cards
+fn deal_runout(rng: &mut StdRng, used: u64, cards: &mut [u8]) {
+    let mut live = [0u8; 52];
+    let mut remaining = 0;
+    for card in (0..52u8).filter(|&card| used & (1u64 << card) == 0) {
+        live[remaining] = card;
+        remaining += 1;
+    }
+    for slot in cards {
+        let pick = rng.random_range(0..remaining);
+        *slot = live[pick];
+        remaining -= 1;
+        live[pick] = live[remaining];
+    }
+}
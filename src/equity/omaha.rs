@@ -1,9 +1,12 @@
 use wasm_bindgen::prelude::*;
-use crate::evaluation::{gen_board_eval, combinations::{HOLE_COMBOS_2_FROM_4, HOLE_COMBOS_2_FROM_5, HOLE_COMBOS_2_FROM_6, BOARD_COMBOS_3_FROM_5}};
-use crate::types::Equity;
+use crate::evaluation::{validate_cards, Board, Card, CardError, EquityError, HandEvaluator, OmahaHand, combinations::{HOLE_COMBOS_2_FROM_4, HOLE_COMBOS_2_FROM_5, HOLE_COMBOS_2_FROM_6, BOARD_COMBOS_3_FROM_5}};
+use crate::types::{BestHand, Equity};
 use crate::range::OmahaRange;
 use rand::Rng;
 
+use super::blocker::dead_card_mask;
+use super::runouts::{board_runouts, map_runout_classes, omaha_symmetries};
+
 /// Output structure for enumerated board runouts
 #[wasm_bindgen]
 #[derive(Debug, Clone, PartialEq)]
@@ -28,8 +31,9 @@ impl RunoutEquities {
 /// Evaluate a single Omaha hand on a complete 5-card board
 /// In Omaha, players MUST use exactly 2 hole cards + exactly 3 board cards
 /// Supports PLO4 (60 combos), PLO5 (100 combos), and PLO6 (150 combos)
-fn eval_omaha_hand(
-    ranks_data: &[u8],
+/// Returns the same rank format as gen_board_eval, see HandRankInfo to decode it
+pub fn eval_omaha_hand<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
     hole_cards: &[u8],
     board: &[u8; 5]
 ) -> i32 {
@@ -48,7 +52,7 @@ fn eval_omaha_hand(
         let board_triple = [board[b1], board[b2], board[b3]];
 
         // Create evaluator for this board combination
-        let hand_eval = gen_board_eval(ranks_data, &board_triple);
+        let hand_eval = evaluator.board_eval(&board_triple);
 
         // Evaluate all possible 2-card hole combinations
         for &[h1, h2] in hole_combos.iter() {
@@ -61,9 +65,46 @@ fn eval_omaha_hand(
     best_rank
 }
 
+/// Like eval_omaha_hand but also returns the 2 hole cards and 3 board cards that make the hand
+pub fn best_omaha_hand<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
+    hole_cards: &[u8],
+    board: &[u8; 5]
+) -> Result<BestHand, String> {
+    let hole_combos: &[[usize; 2]] = match hole_cards.len() {
+        4 => &HOLE_COMBOS_2_FROM_4,
+        5 => &HOLE_COMBOS_2_FROM_5,
+        6 => &HOLE_COMBOS_2_FROM_6,
+        n => return Err(format!("Omaha hand must be 4, 5, or 6 cards, got {n}")),
+    };
+    if validate_cards(hole_cards)? & validate_cards(board)? != 0 {
+        return Err("Hand shares a card with the board".to_string());
+    }
+
+    let mut best = BestHand { cards: [0; 5], rank: i32::MIN };
+
+    for &[b1, b2, b3] in BOARD_COMBOS_3_FROM_5.iter() {
+        let board_triple = [board[b1], board[b2], board[b3]];
+        let hand_eval = evaluator.board_eval(&board_triple);
+
+        for &[h1, h2] in hole_combos.iter() {
+            let hole_pair = [hole_cards[h1], hole_cards[h2]];
+            let rank = hand_eval(&hole_pair);
+            if rank > best.rank {
+                best = BestHand {
+                    cards: [hole_pair[0], hole_pair[1], board_triple[0], board_triple[1], board_triple[2]],
+                    rank,
+                };
+            }
+        }
+    }
+
+    Ok(best)
+}
+
 /// Check if two hands share any cards (works with any hand size)
 #[inline]
-fn hands_overlap(hand1: &[u8], hand2: &[u8]) -> bool {
+pub(crate) fn hands_overlap(hand1: &[u8], hand2: &[u8]) -> bool {
     for &c1 in hand1 {
         for &c2 in hand2 {
             if c1 == c2 {
@@ -76,7 +117,7 @@ fn hands_overlap(hand1: &[u8], hand2: &[u8]) -> bool {
 
 /// Check if a hand overlaps with a 5-card board (works with any hand size)
 #[inline]
-fn hand_overlaps_board(hand: &[u8], board: &[u8; 5]) -> bool {
+pub(crate) fn hand_overlaps_board(hand: &[u8], board: &[u8; 5]) -> bool {
     for &c1 in hand {
         for &c2 in board {
             if c1 == c2 {
@@ -87,6 +128,15 @@ fn hand_overlaps_board(hand: &[u8], board: &[u8; 5]) -> bool {
     false
 }
 
+/// Dead card mask for hero's hand on a board, failing if hero holds a dead card
+pub(crate) fn hero_dead_card_mask(hero_hand: &[u8], dead_cards: &[u8], board: &[u8]) -> Result<u64, String> {
+    let dead_mask = dead_card_mask(dead_cards, board)?;
+    if cards_to_mask(hero_hand) & dead_mask != 0 {
+        return Err("Hero hand holds a dead card".to_string());
+    }
+    Ok(dead_mask)
+}
+
 /// Convert a slice of cards to a bitmask for card removal tracking
 #[inline]
 fn cards_to_mask(cards: &[u8]) -> u64 {
@@ -97,15 +147,59 @@ fn cards_to_mask(cards: &[u8]) -> u64 {
     mask
 }
 
+/// Hero's hand must be 4, 5, or 6 cards, matching the range
+pub(crate) fn check_hero_hand(hero_hand: &[u8], vs_range: &OmahaRange) -> Result<(), String> {
+    if ![4, 5, 6].contains(&hero_hand.len()) {
+        return Err(format!("Omaha hand must be 4, 5, or 6 cards, got {}", hero_hand.len()));
+    }
+    if hero_hand.len() != vs_range.get_hand_size() {
+        return Err(format!(
+            "Hero hand size ({}) must match range hand size ({})",
+            hero_hand.len(),
+            vs_range.get_hand_size()
+        ));
+    }
+    Ok(())
+}
+
 /// Calculate equity for a single Omaha hand vs a range on a complete 5-card board
-pub fn calculate_omaha_leaf_equity(
-    ranks_data: &[u8],
+/// Villain hands holding a dead card are skipped
+/// Fails on invalid or repeated cards, a hero hand on the board or holding a dead card, and dead
+/// cards that are on the board
+pub fn calculate_omaha_leaf_equity<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
     hero_hand: &[u8],
     vs_range: &OmahaRange,
     board: &[u8; 5],
+    dead_cards: &[u8],
+) -> Result<RunoutEquities, String> {
+    let dead_mask = leaf_dead_card_mask(hero_hand, vs_range, board, dead_cards)?;
+    Ok(leaf_equity_with_dead_mask(evaluator, hero_hand, vs_range, board, dead_mask))
+}
+
+/// Checks a leaf's hero hand, board and dead cards, returning the dead card mask
+pub(crate) fn leaf_dead_card_mask(
+    hero_hand: &[u8],
+    vs_range: &OmahaRange,
+    board: &[u8; 5],
+    dead_cards: &[u8],
+) -> Result<u64, String> {
+    check_hero_hand(hero_hand, vs_range)?;
+    if validate_cards(hero_hand)? & validate_cards(board)? != 0 {
+        return Err("Hero hand and board share a card".to_string());
+    }
+    hero_dead_card_mask(hero_hand, dead_cards, board)
+}
+
+fn leaf_equity_with_dead_mask<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
+    hero_hand: &[u8],
+    vs_range: &OmahaRange,
+    board: &[u8; 5],
+    dead_mask: u64,
 ) -> RunoutEquities {
     // Evaluate hero's hand
-    let hero_rank = eval_omaha_hand(ranks_data, hero_hand, board);
+    let hero_rank = eval_omaha_hand(evaluator, hero_hand, board);
 
     // Calculate equity vs range
     let mut win_weight = 0.0;
@@ -115,11 +209,12 @@ pub fn calculate_omaha_leaf_equity(
     for (villain_hand, weight) in vs_range.iter() {
         // Check for card removal/blocking
         if hands_overlap(hero_hand, villain_hand) ||
-           hand_overlaps_board(villain_hand, board) {
+           hand_overlaps_board(villain_hand, board) ||
+           cards_to_mask(villain_hand) & dead_mask != 0 {
             continue;  // This villain combo is impossible
         }
 
-        let villain_rank = eval_omaha_hand(ranks_data, villain_hand, board);
+        let villain_rank = eval_omaha_hand(evaluator, villain_hand, board);
 
         if hero_rank > villain_rank {
             win_weight += weight;
@@ -140,123 +235,89 @@ pub fn calculate_omaha_leaf_equity(
     }
 }
 
-/// Enumerate all river runouts from a turn (4-card board)
-fn calculate_omaha_equity_from_turn(
-    ranks_data: &[u8],
+/// Enumerate all river runouts from a turn (4-card board), one per suit isomorphic class
+fn calculate_omaha_equity_from_turn<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
     hero_hand: &[u8],
     vs_range: &OmahaRange,
     board: &[u8; 4],
+    dead_mask: u64,
 ) -> Vec<RunoutEquities> {
-    let used_mask = cards_to_mask(board) | cards_to_mask(hero_hand);
-    let mut results = Vec::with_capacity(44);
+    let used_mask = cards_to_mask(board) | cards_to_mask(hero_hand) | dead_mask;
 
-    // Enumerate all river cards
-    for river in 0..52u8 {
-        if (used_mask & (1u64 << river)) != 0 {
-            continue;
-        }
+    let symmetries = omaha_symmetries(hero_hand, vs_range, board, dead_mask);
 
-        let full_board = [
-            board[0], board[1], board[2], board[3],
-            river
-        ];
-
-        let equity_result = calculate_omaha_leaf_equity(
-            ranks_data,
-            hero_hand,
-            vs_range,
-            &full_board
-        );
-
-        results.push(equity_result);
-    }
-
-    results
+    map_runout_classes(
+        &board_runouts(board, used_mask),
+        &symmetries,
+        |full_board| leaf_equity_with_dead_mask(evaluator, hero_hand, vs_range, full_board, dead_mask),
+        |result, full_board| result.board = full_board,
+    )
 }
 
-/// Enumerate all turn and river runouts from a flop (3-card board)
-fn calculate_omaha_equity_from_flop(
-    ranks_data: &[u8],
+/// Enumerate all turn and river runouts from a flop (3-card board), one per suit isomorphic class
+fn calculate_omaha_equity_from_flop<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
     hero_hand: &[u8],
     vs_range: &OmahaRange,
     board: &[u8; 3],
+    dead_mask: u64,
 ) -> Vec<RunoutEquities> {
-    let used_mask = cards_to_mask(board) | cards_to_mask(hero_hand);
-    // Pre-allocate: ~45 turn cards × ~44 river cards
-    let mut results = Vec::with_capacity(1980);
-
-    // Enumerate all turn cards
-    for turn in 0..52u8 {
-        if (used_mask & (1u64 << turn)) != 0 {
-            continue;
-        }
-
-        let turn_mask = used_mask | (1u64 << turn);
-
-        // Enumerate all river cards
-        for river in (turn + 1)..52u8 {
-            if (turn_mask & (1u64 << river)) != 0 {
-                continue;
-            }
-
-            let full_board = [
-                board[0], board[1], board[2],
-                turn, river
-            ];
+    let used_mask = cards_to_mask(board) | cards_to_mask(hero_hand) | dead_mask;
 
-            let equity_result = calculate_omaha_leaf_equity(
-                ranks_data,
-                hero_hand,
-                vs_range,
-                &full_board
-            );
-
-            results.push(equity_result);
-        }
-    }
+    let symmetries = omaha_symmetries(hero_hand, vs_range, board, dead_mask);
 
-    results
+    map_runout_classes(
+        &board_runouts(board, used_mask),
+        &symmetries,
+        |full_board| leaf_equity_with_dead_mask(evaluator, hero_hand, vs_range, full_board, dead_mask),
+        |result, full_board| result.board = full_board,
+    )
 }
 
 /// Calculate Omaha equity vs range with board enumeration
-/// Returns equity for each possible runout
-pub fn calculate_omaha_equity_vs_range(
-    ranks_data: &[u8],
+/// Returns equity for each possible runout, dead cards never come and block villain hands
+pub fn calculate_omaha_equity_vs_range<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
     hero_hand: &[u8],
     vs_range: &OmahaRange,
     board: &[u8],
+    dead_cards: &[u8],
 ) -> Result<Vec<RunoutEquities>, String> {
-    // Validate hand size
-    if ![4, 5, 6].contains(&hero_hand.len()) {
-        return Err(format!("Omaha hand must be 4, 5, or 6 cards, got {}", hero_hand.len()));
-    }
-
-    // Validate range matches hero hand size
-    if hero_hand.len() != vs_range.get_hand_size() {
-        return Err(format!(
-            "Hero hand size ({}) must match range hand size ({})",
-            hero_hand.len(),
-            vs_range.get_hand_size()
-        ));
-    }
+    check_hero_hand(hero_hand, vs_range)?;
+    let dead_mask = hero_dead_card_mask(hero_hand, dead_cards, board)?;
 
     match board.len() {
         3 => {
             let board_cards = [board[0], board[1], board[2]];
-            Ok(calculate_omaha_equity_from_flop(ranks_data, hero_hand, vs_range, &board_cards))
+            Ok(calculate_omaha_equity_from_flop(evaluator, hero_hand, vs_range, &board_cards, dead_mask))
         }
         4 => {
             let board_cards = [board[0], board[1], board[2], board[3]];
-            Ok(calculate_omaha_equity_from_turn(ranks_data, hero_hand, vs_range, &board_cards))
+            Ok(calculate_omaha_equity_from_turn(evaluator, hero_hand, vs_range, &board_cards, dead_mask))
         }
         5 => {
             let board_cards = [board[0], board[1], board[2], board[3], board[4]];
-            Ok(vec![calculate_omaha_leaf_equity(ranks_data, hero_hand, vs_range, &board_cards)])
+            Ok(vec![leaf_equity_with_dead_mask(evaluator, hero_hand, vs_range, &board_cards, dead_mask)])
         }
         _ => Err("Board must be 3, 4, or 5 cards".to_string())
     }
 }
 
+/// calculate_omaha_equity_vs_range with validated cards, fails if the hand is on the board
+/// or doesn't match the range's hand size
+pub fn calculate_omaha_equity_vs_range_typed<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
+    hero_hand: &OmahaHand,
+    vs_range: &OmahaRange,
+    board: &Board,
+    dead_cards: &[Card],
+) -> Result<Vec<RunoutEquities>, EquityError> {
+    board.check_hand(hero_hand.as_bytes())?;
+    let dead_cards: Vec<u8> = dead_cards.iter().map(|&card| card.into()).collect();
+    calculate_omaha_equity_vs_range(evaluator, hero_hand.as_bytes(), vs_range, board.as_bytes(), &dead_cards).map_err(EquityError::Invalid)
+}
+
 /// Sample 2 random cards from available deck (avoiding used cards)
 /// Returns None if unable to sample (shouldn't happen with valid inputs)
 fn sample_two_cards(used_mask: u64) -> Option<[u8; 2]> {
@@ -281,16 +342,18 @@ fn sample_two_cards(used_mask: u64) -> Option<[u8; 2]> {
 }
 
 /// Monte Carlo simulation for Omaha equity on the flop
-/// Samples `num_runouts` random turn and river combinations
+/// Samples `num_runouts` random turn and river combinations without the dead cards
 /// Returns equity for each sampled runout
-pub fn calculate_omaha_equity_monte_carlo_flop(
-    ranks_data: &[u8],
+pub fn calculate_omaha_equity_monte_carlo_flop<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
     hero_hand: &[u8],
     vs_range: &OmahaRange,
     flop: &[u8; 3],
     num_runouts: usize,
+    dead_cards: &[u8],
 ) -> Vec<RunoutEquities> {
-    let used_mask = cards_to_mask(flop) | cards_to_mask(hero_hand);
+    let dead_mask = cards_to_mask(dead_cards);
+    let used_mask = cards_to_mask(flop) | cards_to_mask(hero_hand) | dead_mask;
     let mut results = Vec::with_capacity(num_runouts);
 
     for _ in 0..num_runouts {
@@ -298,11 +361,12 @@ pub fn calculate_omaha_equity_monte_carlo_flop(
         if let Some([turn, river]) = sample_two_cards(used_mask) {
             let full_board = [flop[0], flop[1], flop[2], turn, river];
 
-            let runout_equity = calculate_omaha_leaf_equity(
-                ranks_data,
+            let runout_equity = leaf_equity_with_dead_mask(
+                evaluator,
                 hero_hand,
                 vs_range,
                 &full_board,
+                dead_mask,
             );
 
             results.push(runout_equity);
@@ -310,4 +374,30 @@ pub fn calculate_omaha_equity_monte_carlo_flop(
     }
 
     results
-}
\ No newline at end of file
+}
+
+/// calculate_omaha_equity_monte_carlo_flop with validated cards, fails with WrongCount unless the
+/// board is a flop
+pub fn calculate_omaha_equity_monte_carlo_flop_typed<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
+    hero_hand: &OmahaHand,
+    vs_range: &OmahaRange,
+    flop: &Board,
+    num_runouts: usize,
+    dead_cards: &[Card],
+) -> Result<Vec<RunoutEquities>, EquityError> {
+    let &[f1, f2, f3] = flop.as_bytes() else {
+        return Err(CardError::WrongCount { expected: "3", actual: flop.as_bytes().len() }.into());
+    };
+    flop.check_hand(hero_hand.as_bytes())?;
+    let dead_cards: Vec<u8> = dead_cards.iter().map(|&card| card.into()).collect();
+    hero_dead_card_mask(hero_hand.as_bytes(), &dead_cards, flop.as_bytes())?;
+    Ok(calculate_omaha_equity_monte_carlo_flop(
+        evaluator,
+        hero_hand.as_bytes(),
+        vs_range,
+        &[f1, f2, f3],
+        num_runouts,
+        &dead_cards,
+    ))
+}
@@ -0,0 +1,287 @@
+use wasm_bindgen::prelude::*;
+use crate::evaluation::{cards_to_mask, Board, Card, EquityError, HandEvaluator, OmahaHand, combinations::{HOLE_COMBOS_2_FROM_4, HOLE_COMBOS_2_FROM_5, HOLE_COMBOS_2_FROM_6, BOARD_COMBOS_3_FROM_5}};
+use crate::range::OmahaRange;
+use super::omaha::{check_hero_hand, eval_omaha_hand, hand_overlaps_board, hands_overlap, hero_dead_card_mask, leaf_dead_card_mask};
+use super::runouts::{board_runouts, map_runout_classes, omaha_symmetries};
+
+/// Hi/Lo (8 or better) outcome weights for a hero hand vs a range
+///
+/// Each unblocked villain hand adds its weight to `total` and weight * hero's pot share to
+/// `share`, so share / total is hero's expected fraction of the pot. The outcome fields hold
+/// the weight of villain hands where hero:
+/// - scoop: wins the whole pot
+/// - high_only: wins the high half outright and gets nothing from the low
+/// - low_only: wins the low half outright and gets nothing from the high
+/// - quartered: ends up with a quarter of the pot (splits the low, no high)
+///
+/// Other outcomes (split pots, three quarters, losing both halves) only count towards share
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct HiLoEquity {
+    pub(crate) scoop: f32,
+    pub(crate) high_only: f32,
+    pub(crate) low_only: f32,
+    pub(crate) quartered: f32,
+    pub(crate) share: f32,
+    pub(crate) total: f32,
+}
+
+#[wasm_bindgen]
+impl HiLoEquity {
+    #[wasm_bindgen(getter)]
+    pub fn scoop(&self) -> f32 {
+        self.scoop
+    }
+
+    #[wasm_bindgen(getter, js_name = highOnly)]
+    pub fn high_only(&self) -> f32 {
+        self.high_only
+    }
+
+    #[wasm_bindgen(getter, js_name = lowOnly)]
+    pub fn low_only(&self) -> f32 {
+        self.low_only
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn quartered(&self) -> f32 {
+        self.quartered
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn share(&self) -> f32 {
+        self.share
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn total(&self) -> f32 {
+        self.total
+    }
+}
+
+/// Hi/Lo equity for one complete board
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunoutHiLoEquities {
+    pub(crate) board: [u8; 5],
+    pub(crate) equity: HiLoEquity,
+}
+
+#[wasm_bindgen]
+impl RunoutHiLoEquities {
+    #[wasm_bindgen(getter)]
+    pub fn board(&self) -> Vec<u8> {
+        self.board.to_vec()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn equity(&self) -> HiLoEquity {
+        self.equity
+    }
+}
+
+/// Bit of a card's rank in an ace to eight low (ace = bit 0), 0 for nines and above
+#[inline]
+fn low_bit(card: u8) -> u8 {
+    match card / 4 {
+        12 => 1,
+        rank @ 0..=6 => 1 << (rank + 1),
+        _ => 0,
+    }
+}
+
+/// Evaluate the 8 or better low of an Omaha hand on a complete 5-card board
+/// Like the high hand it must use exactly 2 hole cards + exactly 3 board cards
+///
+/// The low is returned as 256 - bits, where bits masks the five low ranks with the ace at bit 0
+/// and 2-8 at bits 1-7. A lower top card always means a smaller mask, so higher is better:
+/// A-2-3-4-5 gives 256 - 0b11111 = 225 and 8-7-6-5-4 the worst low, 256 - 0b11111000 = 8.
+/// 0 means no qualifying low
+///
+/// # Panics
+///
+/// Panics if the hand doesn't have 4, 5, or 6 cards
+pub fn eval_omaha_low(hole_cards: &[u8], board: &[u8; 5]) -> i32 {
+    let hole_combos: &[[usize; 2]] = match hole_cards.len() {
+        4 => &HOLE_COMBOS_2_FROM_4,
+        5 => &HOLE_COMBOS_2_FROM_5,
+        6 => &HOLE_COMBOS_2_FROM_6,
+        _ => panic!("Invalid Omaha hand size: {}", hole_cards.len()),
+    };
+
+    let mut best_rank = 0;
+    for &[b1, b2, b3] in BOARD_COMBOS_3_FROM_5.iter() {
+        let board_bits = low_bit(board[b1]) | low_bit(board[b2]) | low_bit(board[b3]);
+        if board_bits.count_ones() != 3 {
+            continue;
+        }
+
+        for &[h1, h2] in hole_combos.iter() {
+            let bits = board_bits | low_bit(hole_cards[h1]) | low_bit(hole_cards[h2]);
+            // 5 distinct low ranks, comparing the masks compares from the highest card down
+            if bits.count_ones() == 5 {
+                best_rank = best_rank.max(256 - bits as i32);
+            }
+        }
+    }
+
+    best_rank
+}
+
+/// Calculate Hi/Lo equity for a single Omaha hand vs a range on a complete 5-card board
+/// Villain hands holding a dead card are skipped
+/// Fails on invalid or repeated cards, a hero hand on the board or holding a dead card, and dead
+/// cards that are on the board
+pub fn calculate_omaha_hilo_leaf_equity<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
+    hero_hand: &[u8],
+    vs_range: &OmahaRange,
+    board: &[u8; 5],
+    dead_cards: &[u8],
+) -> Result<RunoutHiLoEquities, String> {
+    let dead_mask = leaf_dead_card_mask(hero_hand, vs_range, board, dead_cards)?;
+    Ok(hilo_leaf_equity_with_dead_mask(evaluator, hero_hand, vs_range, board, dead_mask))
+}
+
+/// Hero's hand must have passed check_hero_hand, so it and every villain hand (all the range's
+/// hand size) are 4, 5, or 6 cards and the evaluators can't panic
+fn hilo_leaf_equity_with_dead_mask<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
+    hero_hand: &[u8],
+    vs_range: &OmahaRange,
+    board: &[u8; 5],
+    dead_mask: u64,
+) -> RunoutHiLoEquities {
+    debug_assert!(check_hero_hand(hero_hand, vs_range).is_ok(), "unchecked hero hand");
+    let hero_high = eval_omaha_hand(evaluator, hero_hand, board);
+    let hero_low = eval_omaha_low(hero_hand, board);
+
+    let mut equity = HiLoEquity::default();
+
+    for (villain_hand, weight) in vs_range.iter() {
+        if hands_overlap(hero_hand, villain_hand) ||
+           hand_overlaps_board(villain_hand, board) ||
+           cards_to_mask(villain_hand) & dead_mask != 0 {
+            continue;  // This villain combo is impossible
+        }
+
+        let villain_high = eval_omaha_hand(evaluator, villain_hand, board);
+        let villain_low = eval_omaha_low(villain_hand, board);
+
+        // halves of each half pot: 2 = won, 1 = split, 0 = lost
+        let high_part = match hero_high.cmp(&villain_high) {
+            std::cmp::Ordering::Greater => 2,
+            std::cmp::Ordering::Equal => 1,
+            std::cmp::Ordering::Less => 0,
+        };
+        let has_low = hero_low > 0 || villain_low > 0;
+        let low_part = match hero_low.cmp(&villain_low) {
+            std::cmp::Ordering::Greater => 2,
+            std::cmp::Ordering::Equal if hero_low > 0 => 1,
+            _ => 0,
+        };
+
+        // quarters of the pot, the high hand takes everything without a qualifying low
+        let quarters = if has_low { high_part + low_part } else { 2 * high_part };
+
+        equity.total += weight;
+        equity.share += weight * quarters as f32 / 4.0;
+        if quarters == 4 {
+            equity.scoop += weight;
+        } else if has_low && high_part == 2 && low_part == 0 {
+            equity.high_only += weight;
+        } else if has_low && low_part == 2 && high_part == 0 {
+            equity.low_only += weight;
+        } else if quarters == 1 {
+            equity.quartered += weight;
+        }
+    }
+
+    RunoutHiLoEquities {
+        board: *board,
+        equity,
+    }
+}
+
+/// Enumerate all river runouts from a turn (4-card board), one per suit isomorphic class
+fn calculate_omaha_hilo_equity_from_turn<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
+    hero_hand: &[u8],
+    vs_range: &OmahaRange,
+    board: &[u8; 4],
+    dead_mask: u64,
+) -> Vec<RunoutHiLoEquities> {
+    let used_mask = cards_to_mask(board) | cards_to_mask(hero_hand) | dead_mask;
+
+    let symmetries = omaha_symmetries(hero_hand, vs_range, board, dead_mask);
+
+    map_runout_classes(
+        &board_runouts(board, used_mask),
+        &symmetries,
+        |full_board| hilo_leaf_equity_with_dead_mask(evaluator, hero_hand, vs_range, full_board, dead_mask),
+        |result, full_board| result.board = full_board,
+    )
+}
+
+/// Enumerate all turn and river runouts from a flop (3-card board), one per suit isomorphic class
+fn calculate_omaha_hilo_equity_from_flop<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
+    hero_hand: &[u8],
+    vs_range: &OmahaRange,
+    board: &[u8; 3],
+    dead_mask: u64,
+) -> Vec<RunoutHiLoEquities> {
+    let used_mask = cards_to_mask(board) | cards_to_mask(hero_hand) | dead_mask;
+
+    let symmetries = omaha_symmetries(hero_hand, vs_range, board, dead_mask);
+
+    map_runout_classes(
+        &board_runouts(board, used_mask),
+        &symmetries,
+        |full_board| hilo_leaf_equity_with_dead_mask(evaluator, hero_hand, vs_range, full_board, dead_mask),
+        |result, full_board| result.board = full_board,
+    )
+}
+
+/// Calculate Omaha Hi/Lo equity vs range with board enumeration
+/// Returns equity for each possible runout, dead cards never come and block villain hands
+pub fn calculate_omaha_hilo_equity_vs_range<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
+    hero_hand: &[u8],
+    vs_range: &OmahaRange,
+    board: &[u8],
+    dead_cards: &[u8],
+) -> Result<Vec<RunoutHiLoEquities>, String> {
+    check_hero_hand(hero_hand, vs_range)?;
+    let dead_mask = hero_dead_card_mask(hero_hand, dead_cards, board)?;
+
+    match board.len() {
+        3 => {
+            let board_cards = [board[0], board[1], board[2]];
+            Ok(calculate_omaha_hilo_equity_from_flop(evaluator, hero_hand, vs_range, &board_cards, dead_mask))
+        }
+        4 => {
+            let board_cards = [board[0], board[1], board[2], board[3]];
+            Ok(calculate_omaha_hilo_equity_from_turn(evaluator, hero_hand, vs_range, &board_cards, dead_mask))
+        }
+        5 => {
+            let board_cards = [board[0], board[1], board[2], board[3], board[4]];
+            Ok(vec![hilo_leaf_equity_with_dead_mask(evaluator, hero_hand, vs_range, &board_cards, dead_mask)])
+        }
+        _ => Err("Board must be 3, 4, or 5 cards".to_string())
+    }
+}
+
+/// calculate_omaha_hilo_equity_vs_range with validated cards, fails if the hand is on the board
+/// or doesn't match the range's hand size
+pub fn calculate_omaha_hilo_equity_vs_range_typed<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
+    hero_hand: &OmahaHand,
+    vs_range: &OmahaRange,
+    board: &Board,
+    dead_cards: &[Card],
+) -> Result<Vec<RunoutHiLoEquities>, EquityError> {
+    board.check_hand(hero_hand.as_bytes())?;
+    let dead_cards: Vec<u8> = dead_cards.iter().map(|&card| card.into()).collect();
+    calculate_omaha_hilo_equity_vs_range(evaluator, hero_hand.as_bytes(), vs_range, board.as_bytes(), &dead_cards).map_err(EquityError::Invalid)
+}
@@ -0,0 +1,229 @@
+// Exact preflop Hold'em equity from a table of every heads-up matchup
+//
+// Relabeling the suits of two combos doesn't change their equity vs each other, and neither does
+// swapping who is hero, so the 1326 x 1325 combo matchups collapse into a few tens of thousands of
+// classes. preflop_equity.bin holds the win and tie counts of each class over all 1712304 boards,
+// enumerated exactly by generate_preflop_equities. Range vs range equity then only sums table
+// entries over villain combos, with card removal handled by skipping overlapping combos
+
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+
+use crate::evaluation::{cards_to_mask, fast_eval, next_p, permute_suit, IDX2HAND, SUIT_PERMUTATIONS};
+use crate::range::{hand_class_index, NUM_HAND_CLASSES};
+use crate::{Equity, EquityResult, HoldemRange};
+
+/// Boards dealt to two non-overlapping combos, C(48, 5)
+pub const PREFLOP_BOARDS: u32 = 1_712_304;
+
+/// Win and tie counts per matchup class as little endian u32 pairs, in MatchupLayout order
+static PREFLOP_EQUITY_BYTES: &[u8] = include_bytes!("preflop_equity.bin");
+
+lazy_static! {
+    static ref LAYOUT: MatchupLayout = MatchupLayout::new();
+    static ref COUNTS: Vec<[u32; 2]> = {
+        assert_eq!(
+            PREFLOP_EQUITY_BYTES.len(),
+            LAYOUT.matchups.len() * 8,
+            "preflop_equity.bin doesn't match the matchup classes"
+        );
+        PREFLOP_EQUITY_BYTES
+            .chunks_exact(8)
+            .map(|entry| {
+                let count = |bytes: &[u8]| u32::from_le_bytes(bytes.try_into().expect("4 bytes"));
+                [count(&entry[..4]), count(&entry[4..])]
+            })
+            .collect()
+    };
+}
+
+/// Where a (hero class representative, villain combo) matchup is stored
+#[derive(Clone, Copy)]
+struct Slot {
+    entry: u32,
+    // the table is from the villain's side, so win and lose trade places
+    swapped: bool,
+}
+
+/// Suit isomorphic matchup classes and how every combo vs combo matchup maps onto them
+struct MatchupLayout {
+    /// Suit permutation taking each of the 1326 combos onto its class representative
+    to_representative: Vec<[u8; 4]>,
+    /// Slot of (representative of class, villain hand idx) at class * 1326 + idx, None if they overlap
+    slots: Vec<Option<Slot>>,
+    /// Canonical (hero, villain) of each class in table order
+    matchups: Vec<([u8; 2], [u8; 2])>,
+}
+
+/// Cards of a combo after relabeling suits, highest first
+fn relabel(combo: [u8; 2], perm: &[u8; 4]) -> [u8; 2] {
+    let mut cards = combo.map(|card| permute_suit(card, perm));
+    HoldemRange::sort_two_cards_desc(&mut cards);
+    cards
+}
+
+/// Smallest suit relabeling of hero's then villain's cards
+fn matchup_key(hero: [u8; 2], villain: [u8; 2]) -> [u8; 4] {
+    SUIT_PERMUTATIONS
+        .iter()
+        .map(|perm| {
+            let ([h1, h2], [v1, v2]) = (relabel(hero, perm), relabel(villain, perm));
+            [h1, h2, v1, v2]
+        })
+        .min()
+        .expect("24 permutations")
+}
+
+impl MatchupLayout {
+    fn new() -> Self {
+        // first combo of each of the 169 hand classes
+        let mut representatives = [[0u8; 2]; NUM_HAND_CLASSES];
+        let mut seen = [false; NUM_HAND_CLASSES];
+        for (idx, &combo) in IDX2HAND.iter().enumerate() {
+            let class_idx = hand_class_index(idx);
+            if !seen[class_idx] {
+                seen[class_idx] = true;
+                representatives[class_idx] = combo;
+            }
+        }
+
+        let to_representative = IDX2HAND
+            .iter()
+            .enumerate()
+            .map(|(idx, &combo)| {
+                let target = relabel(representatives[hand_class_index(idx)], &[0, 1, 2, 3]);
+                *SUIT_PERMUTATIONS
+                    .iter()
+                    .find(|perm| relabel(combo, perm) == target)
+                    .expect("combos of a class are suit relabelings of each other")
+            })
+            .collect();
+
+        let mut entries: HashMap<[u8; 4], u32> = HashMap::new();
+        let mut matchups = Vec::new();
+        let mut slots = Vec::with_capacity(NUM_HAND_CLASSES * 1326);
+        for &hero in &representatives {
+            for &villain in IDX2HAND.iter() {
+                if cards_to_mask(&hero) & cards_to_mask(&villain) != 0 {
+                    slots.push(None);
+                    continue;
+                }
+                let (forward, backward) = (matchup_key(hero, villain), matchup_key(villain, hero));
+                let (key, swapped) = if backward < forward { (backward, true) } else { (forward, false) };
+                let entry = *entries.entry(key).or_insert_with(|| {
+                    matchups.push(([key[0], key[1]], [key[2], key[3]]));
+                    (matchups.len() - 1) as u32
+                });
+                slots.push(Some(Slot { entry, swapped }));
+            }
+        }
+
+        MatchupLayout { to_representative, slots, matchups }
+    }
+}
+
+/// Exact win and tie counts of hero vs villain over every board, using the hand-rank table
+fn matchup_counts(hand_ranks: &[u8], hero: [u8; 2], villain: [u8; 2]) -> [u32; 2] {
+    let dead = cards_to_mask(&hero) | cards_to_mask(&villain);
+    let deck: Vec<usize> = (0..52).filter(|&card| dead & (1u64 << card) == 0).collect();
+    let n = deck.len();
+    let next = |p: u32, card: usize| next_p(hand_ranks, p as usize + card);
+
+    let (mut wins, mut ties) = (0u32, 0u32);
+    let hero_p0 = fast_eval(hand_ranks, &hero, 53);
+    let villain_p0 = fast_eval(hand_ranks, &villain, 53);
+    for i1 in 0..n {
+        let (h1, v1) = (next(hero_p0, deck[i1]), next(villain_p0, deck[i1]));
+        for i2 in i1 + 1..n {
+            let (h2, v2) = (next(h1, deck[i2]), next(v1, deck[i2]));
+            for i3 in i2 + 1..n {
+                let (h3, v3) = (next(h2, deck[i3]), next(v2, deck[i3]));
+                for i4 in i3 + 1..n {
+                    let (h4, v4) = (next(h3, deck[i4]), next(v3, deck[i4]));
+                    for &card in &deck[i4 + 1..] {
+                        let (hero_rank, villain_rank) = (next(h4, card), next(v4, card));
+                        wins += (hero_rank > villain_rank) as u32;
+                        ties += (hero_rank == villain_rank) as u32;
+                    }
+                }
+            }
+        }
+    }
+    [wins, ties]
+}
+
+/// Exact win and tie counts of every matchup class, the contents of preflop_equity.bin
+/// `hand_ranks` is the HandRanks.dat table, enumerating every class takes a few minutes
+pub fn generate_preflop_equities(hand_ranks: &[u8]) -> Vec<u8> {
+    LAYOUT
+        .matchups
+        .iter()
+        .flat_map(|&(hero, villain)| {
+            let [wins, ties] = matchup_counts(hand_ranks, hero, villain);
+            wins.to_le_bytes().into_iter().chain(ties.to_le_bytes())
+        })
+        .collect()
+}
+
+/// Generates the hand-rank table and writes the preflop equity table to `path`
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write_preflop_equities<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<()> {
+    let hand_ranks = crate::evaluation::generate_hand_ranks();
+    std::fs::write(path, generate_preflop_equities(&hand_ranks))
+}
+
+/// Exact preflop equity of hero's combo vs villain's as (win, tie) board counts out of
+/// PREFLOP_BOARDS, None if the combos share a card
+pub fn preflop_matchup(hero: [u8; 2], villain: [u8; 2]) -> Option<[u32; 2]> {
+    let hero_idx = HoldemRange::get_hand_idx(hero);
+    let perm = &LAYOUT.to_representative[hero_idx];
+    let villain_idx = HoldemRange::get_hand_idx(relabel(villain, perm));
+    let slot = LAYOUT.slots[hand_class_index(hero_idx) * 1326 + villain_idx]?;
+    let [wins, ties] = COUNTS[slot.entry as usize];
+    Some(if slot.swapped { [PREFLOP_BOARDS - wins - ties, ties] } else { [wins, ties] })
+}
+
+/// Exact preflop equity of every hero combo vs the villain range
+/// Like the postflop enumeration, win/tie/lose are villain weights summed over every board, so
+/// each live villain combo adds its weight * PREFLOP_BOARDS
+pub fn calculate_preflop_equity_vs_range(hero_range: &HoldemRange, vs_range: &HoldemRange) -> Vec<EquityResult> {
+    let mut villains = Vec::new();
+    vs_range.for_each_weighted(|weight, idx| villains.push((IDX2HAND[idx], weight as f64)));
+
+    let mut results = Vec::new();
+    hero_range.for_each_weighted(|_weight, hand_idx| {
+        let combo = HoldemRange::from_hand_idx(hand_idx);
+        let perm = &LAYOUT.to_representative[hand_idx];
+        let class_slots = &LAYOUT.slots[hand_class_index(hand_idx) * 1326..][..1326];
+
+        let (mut win, mut tie, mut total) = (0.0f64, 0.0f64, 0.0f64);
+        for &(villain, weight) in &villains {
+            let Some(slot) = class_slots[HoldemRange::get_hand_idx(relabel(villain, perm))] else {
+                continue;
+            };
+            let [wins, ties] = COUNTS[slot.entry as usize];
+            let wins = if slot.swapped { PREFLOP_BOARDS - wins - ties } else { wins };
+            win += weight * wins as f64;
+            tie += weight * ties as f64;
+            total += weight;
+        }
+        total *= PREFLOP_BOARDS as f64;
+
+        results.push(EquityResult {
+            combo,
+            hand_idx,
+            equity: Equity {
+                win: win as f32,
+                tie: tie as f32,
+                lose: (total - win - tie) as f32,
+            },
+        });
+    });
+    results
+}
+
+/// Suit isomorphic matchup classes in the table, preflop_equity.bin has 8 bytes per class
+pub fn preflop_matchup_classes() -> usize {
+    LAYOUT.matchups.len()
+}
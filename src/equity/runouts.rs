@@ -0,0 +1,177 @@
+// Turn and river runouts, grouped by suit isomorphism
+//
+// A suit permutation that maps the board, the dead cards and every hand or range onto themselves
+// turns each runout into another one that's dealt just as often and has the same results for the
+// relabeled hands. Those permutations form a group, so the runouts split into classes (orbits)
+// and the enumerators evaluate one representative per class. On a rainbow flop only the identity
+// fixes the board, on a two-tone flop the two missing suits can swap and on a monotone flop the
+// other three suits can be shuffled in any order
+
+use std::collections::HashMap;
+
+use crate::evaluation::{cards_to_mask, permute_suit, SUIT_PERMUTATIONS};
+use crate::range::OmahaRange;
+use crate::HoldemRange;
+
+const IDENTITY: [u8; 4] = [0, 1, 2, 3];
+
+/// Every complete board reachable from a 3 or 4 card board, skipping cards in used_mask
+/// Turns come before rivers and each turn/river pair appears once with the lower card as the turn
+pub(crate) fn board_runouts(board: &[u8], used_mask: u64) -> Vec<[u8; 5]> {
+    let mut runouts = Vec::new();
+
+    if board.len() == 4 {
+        // Enumerate all river cards
+        for river in 0..52u8 {
+            if (used_mask & (1u64 << river)) != 0 {
+                continue;
+            }
+            runouts.push([board[0], board[1], board[2], board[3], river]);
+        }
+    } else if board.len() == 3 {
+        // Pre-allocate: ~45 turn cards × ~44 river cards
+        runouts.reserve(1980);
+
+        // Enumerate all turn cards
+        for turn in 0..52u8 {
+            if (used_mask & (1u64 << turn)) != 0 {
+                continue;
+            }
+
+            let turn_mask = used_mask | (1u64 << turn);
+
+            // Enumerate all river cards
+            for river in (turn + 1)..52u8 {
+                if (turn_mask & (1u64 << river)) != 0 {
+                    continue;
+                }
+                runouts.push([board[0], board[1], board[2], turn, river]);
+            }
+        }
+    }
+
+    runouts
+}
+
+/// Relabels the suits of every card in a mask
+pub(crate) fn permute_mask(mask: u64, perm: &[u8; 4]) -> u64 {
+    let mut permuted = 0u64;
+    let mut rest = mask;
+    while rest != 0 {
+        let card = rest.trailing_zeros() as u8;
+        rest &= rest - 1;
+        permuted |= 1u64 << permute_suit(card, perm);
+    }
+    permuted
+}
+
+/// Suit permutations mapping each of `masks` onto itself and passing `fixes`, identity first
+/// `fixes` only sees permutations that already fix the masks, so it can hold the costly checks
+fn suit_symmetries(masks: &[u64], fixes: impl Fn(&[u8; 4]) -> bool) -> Vec<[u8; 4]> {
+    SUIT_PERMUTATIONS
+        .iter()
+        .filter(|perm| {
+            **perm == IDENTITY
+                || (masks.iter().all(|&mask| permute_mask(mask, perm) == mask) && fixes(perm))
+        })
+        .copied()
+        .collect()
+}
+
+fn holdem_range_is_symmetric(range: &HoldemRange, perm: &[u8; 4]) -> bool {
+    (0..1326).all(|idx| {
+        let permuted = HoldemRange::from_hand_idx(idx).map(|card| permute_suit(card, perm));
+        range.range[HoldemRange::get_hand_idx(permuted)] == range.range[idx]
+    })
+}
+
+fn omaha_range_is_symmetric(range: &OmahaRange, perm: &[u8; 4]) -> bool {
+    let mut permuted = [0u8; 6];
+    range.iter().all(|(hand, weight)| {
+        for (slot, &card) in permuted.iter_mut().zip(hand) {
+            *slot = permute_suit(card, perm);
+        }
+        range.weight_of(&permuted[..hand.len()]) == Some(weight)
+    })
+}
+
+/// Suit symmetries of a Hold'em spot: the board, dead cards and both ranges stay the same
+pub(crate) fn holdem_symmetries(
+    hero_range: &HoldemRange,
+    vs_range: &HoldemRange,
+    board: &[u8],
+    dead_mask: u64,
+) -> Vec<[u8; 4]> {
+    suit_symmetries(&[cards_to_mask(board), dead_mask], |perm| {
+        holdem_range_is_symmetric(hero_range, perm) && holdem_range_is_symmetric(vs_range, perm)
+    })
+}
+
+/// Suit symmetries of an Omaha spot: the board, dead cards, hero's hand and the range stay the same
+pub(crate) fn omaha_symmetries(
+    hero_hand: &[u8],
+    vs_range: &OmahaRange,
+    board: &[u8],
+    dead_mask: u64,
+) -> Vec<[u8; 4]> {
+    suit_symmetries(&[cards_to_mask(board), dead_mask, cards_to_mask(hero_hand)], |perm| {
+        omaha_range_is_symmetric(vs_range, perm)
+    })
+}
+
+/// Runouts that are suit relabelings of each other
+pub(crate) struct RunoutClass {
+    /// Index of the runout that gets evaluated
+    pub representative: usize,
+    /// Index of every runout in the class with a permutation taking the representative onto it
+    pub members: Vec<(usize, [u8; 4])>,
+}
+
+/// Groups runouts of one board by the symmetries of the spot, classes in order of first runout
+pub(crate) fn runout_classes(runouts: &[[u8; 5]], symmetries: &[[u8; 4]]) -> Vec<RunoutClass> {
+    let mut classes: Vec<RunoutClass> = Vec::new();
+    let mut class_of: HashMap<u64, usize> = HashMap::new();
+
+    for (idx, runout) in runouts.iter().enumerate() {
+        let mask = cards_to_mask(runout);
+        // perm maps this runout onto a representative, so its inverse maps the representative back
+        let seen = symmetries
+            .iter()
+            .find_map(|perm| class_of.get(&permute_mask(mask, perm)).map(|&class_idx| (class_idx, perm)));
+        match seen {
+            Some((class_idx, perm)) => {
+                let mut inverse = [0u8; 4];
+                for (suit, &target) in perm.iter().enumerate() {
+                    inverse[target as usize] = suit as u8;
+                }
+                classes[class_idx].members.push((idx, inverse));
+            }
+            None => {
+                class_of.insert(mask, classes.len());
+                classes.push(RunoutClass { representative: idx, members: vec![(idx, IDENTITY)] });
+            }
+        }
+    }
+
+    classes
+}
+
+/// Runs `leaf` on one runout per class and copies its result to the rest of the class, giving
+/// results in runout order. `set_board` points a copied result at its own runout
+pub(crate) fn map_runout_classes<T: Clone>(
+    runouts: &[[u8; 5]],
+    symmetries: &[[u8; 4]],
+    mut leaf: impl FnMut(&[u8; 5]) -> T,
+    set_board: impl Fn(&mut T, [u8; 5]),
+) -> Vec<T> {
+    let mut results: Vec<Option<T>> = vec![None; runouts.len()];
+    for class in runout_classes(runouts, symmetries) {
+        let result = leaf(&runouts[class.representative]);
+        for &(idx, _) in &class.members {
+            let mut copy = result.clone();
+            set_board(&mut copy, runouts[idx]);
+            results[idx] = Some(copy);
+        }
+    }
+    results.into_iter().map(|result| result.expect("every runout is in a class")).collect()
+}
@@ -0,0 +1,64 @@
+use crate::evaluation::{is_short_deck_card, validate_cards, ShortDeckEvaluator, SHORT_DECK_MASK};
+use crate::range::ShortDeckRange;
+use crate::EquityResult;
+
+use super::blocker::dead_card_mask;
+use super::holdem::{enumerate_equity_vs_range, leaf_equity_with_dead_mask};
+
+fn check_short_deck_cards(cards: &[u8]) -> Result<(), String> {
+    match cards.iter().find(|&&card| !is_short_deck_card(card)) {
+        Some(&card) => Err(format!("Card {card} is not in the Short Deck")),
+        None => Ok(()),
+    }
+}
+
+/// Checks the board and dead cards, returning the dead card mask
+fn validate_short_deck_cards(board: &[u8], dead_cards: &[u8]) -> Result<u64, String> {
+    if board.len() < 3 || board.len() > 5 {
+        return Err("Board must have 3, 4, or 5 cards".to_string());
+    }
+    validate_cards(board)?;
+    check_short_deck_cards(board)?;
+    let dead_mask = dead_card_mask(dead_cards, board)?;
+    check_short_deck_cards(dead_cards)?;
+    Ok(dead_mask)
+}
+
+/// Short Deck equity on a complete 5 card board, combos holding a dead card are skipped
+/// Fails on cards outside the 36 card deck and dead cards that are on the board
+pub fn calculate_short_deck_leaf_equity(
+    hero_range: &ShortDeckRange,
+    vs_range: &ShortDeckRange,
+    board: &[u8],
+    dead_cards: &[u8],
+) -> Result<Vec<EquityResult>, String> {
+    if board.len() != 5 {
+        return Err("Board must be exactly 5 cards".to_string());
+    }
+    let dead_mask = validate_short_deck_cards(board, dead_cards)?;
+
+    Ok(leaf_equity_with_dead_mask(&ShortDeckEvaluator, &hero_range.range, &vs_range.range, board, dead_mask))
+}
+
+/// Short Deck equity with board enumeration (3, 4, or 5-card boards)
+/// Runouts only come from the 36 card deck, dead cards never come and block combos holding them
+pub fn calculate_short_deck_equity_vs_range(
+    hero_range: &ShortDeckRange,
+    vs_range: &ShortDeckRange,
+    board: &[u8],
+    dead_cards: &[u8],
+) -> Result<Vec<EquityResult>, String> {
+    let dead_mask = validate_short_deck_cards(board, dead_cards)?;
+
+    if board.len() == 5 {
+        return calculate_short_deck_leaf_equity(hero_range, vs_range, board, dead_cards);
+    }
+
+    Ok(enumerate_equity_vs_range(
+        &ShortDeckEvaluator,
+        &hero_range.range,
+        &vs_range.range,
+        board,
+        SHORT_DECK_MASK & !dead_mask,
+    ))
+}
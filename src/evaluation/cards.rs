@@ -1,7 +1,14 @@
+use wasm_bindgen::prelude::*;
+
+use super::typed::Card;
+
 // Card representation constants
 pub const SUITS: &str = "cdhs";
 pub const RANKS: &str = "23456789TJQKA";
 
+/// Mask of all 52 cards
+pub const FULL_DECK_MASK: u64 = (1u64 << 52) - 1;
+
 // Lookup table from hand index to combo
 pub static IDX2HAND: [[u8; 2]; 1326] = {
     let mut hands = [[0u8; 2]; 1326];
@@ -19,19 +26,34 @@ pub static IDX2HAND: [[u8; 2]; 1326] = {
     hands
 };
 
-// Card string formatting functions
-pub fn card_to_string(card: u8) -> String {
-    if card >= 52 {
-        return "??".to_string();
+/// Every relabeling of the 4 suits, the new suit of `suit` is SUIT_PERMUTATIONS[i][suit]
+pub(crate) static SUIT_PERMUTATIONS: [[u8; 4]; 24] = {
+    let mut permutations = [[0u8; 4]; 24];
+    let mut idx = 0;
+    let mut code = 0;
+    // every 4 digit base 4 number whose digits are all different
+    while code < 256 {
+        let perm = [(code >> 6) as u8 & 3, (code >> 4) as u8 & 3, (code >> 2) as u8 & 3, code as u8 & 3];
+        let seen = (1u8 << perm[0]) | (1u8 << perm[1]) | (1u8 << perm[2]) | (1u8 << perm[3]);
+        if seen == 0b1111 {
+            permutations[idx] = perm;
+            idx += 1;
+        }
+        code += 1;
     }
+    permutations
+};
 
-    let rank_idx = (card / 4) as usize;
-    let suit_idx = (card % 4) as usize;
-
-    let rank_char = RANKS.chars().nth(rank_idx).unwrap_or('?');
-    let suit_char = SUITS.chars().nth(suit_idx).unwrap_or('?');
+/// A card with its suit relabeled by one of SUIT_PERMUTATIONS
+#[inline]
+pub(crate) fn permute_suit(card: u8, perm: &[u8; 4]) -> u8 {
+    card & !3 | perm[(card & 3) as usize]
+}
 
-    format!("{rank_char}{suit_char}")
+// Card string formatting functions
+/// Two character name like "As", or "??" for a card outside 0-51
+pub fn card_to_string(card: u8) -> String {
+    Card::try_from(card).map_or_else(|_| "??".to_string(), |card| card.to_string())
 }
 
 pub fn string_to_card(card_str: &str) -> Option<u8> {
@@ -67,3 +89,77 @@ pub fn cards_to_mask(cards: &[u8]) -> u64 {
 pub fn board_to_mask(board: &[u8]) -> u64 {
     cards_to_mask(board)
 }
+
+/// Checks cards are 0-51 and distinct, returning their mask
+pub fn validate_cards(cards: &[u8]) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for &card in cards {
+        if card >= 52 {
+            return Err(format!("Invalid card {card}, cards must be 0-51"));
+        }
+        if mask & (1u64 << card) != 0 {
+            return Err(format!("Duplicate card {}", card_to_string(card)));
+        }
+        mask |= 1u64 << card;
+    }
+    Ok(mask)
+}
+
+/// Reads "AhKd7c", "Ah Kd 7c" or "ah,kd,7c" into card bytes without checking for duplicates
+pub(crate) fn read_cards(text: &str) -> Result<Vec<u8>, String> {
+    let chars: Vec<(usize, char)> = text
+        .char_indices()
+        .filter(|&(_, c)| !c.is_whitespace() && c != ',')
+        .collect();
+
+    let mut cards = Vec::with_capacity(chars.len() / 2);
+    for pair in chars.chunks(2) {
+        let (position, rank) = pair[0];
+        let Some(&(_, suit)) = pair.get(1) else {
+            return Err(format!("Missing suit for '{rank}' at position {position}"));
+        };
+        let card = format!("{}{}", rank.to_ascii_uppercase(), suit.to_ascii_lowercase());
+        match string_to_card(&card) {
+            Some(card) => cards.push(card),
+            None => return Err(format!("Invalid card '{rank}{suit}' at position {position}")),
+        }
+    }
+
+    Ok(cards)
+}
+
+/// Parse cards like "AhKd7c", "Ah Kd 7c" or "ah,kd,7c" into card bytes (rank * 4 + suit)
+/// Fails on unknown ranks or suits, a dangling character, or duplicate cards
+#[wasm_bindgen(js_name = parseCards)]
+pub fn parse_cards(text: &str) -> Result<Vec<u8>, String> {
+    let cards = read_cards(text)?;
+    validate_cards(&cards)?;
+    Ok(cards)
+}
+
+/// Parse a 3, 4, or 5 card board
+#[wasm_bindgen(js_name = parseBoard)]
+pub fn parse_board(text: &str) -> Result<Vec<u8>, String> {
+    let cards = parse_cards(text)?;
+    if !(3..=5).contains(&cards.len()) {
+        return Err(format!("Board must have 3, 4, or 5 cards, got {}", cards.len()));
+    }
+    Ok(cards)
+}
+
+/// Parse a 2 card Hold'em hand or a 4, 5, or 6 card Omaha hand
+#[wasm_bindgen(js_name = parseHand)]
+pub fn parse_hand(text: &str) -> Result<Vec<u8>, String> {
+    let cards = parse_cards(text)?;
+    if ![2, 4, 5, 6].contains(&cards.len()) {
+        return Err(format!("Hand must have 2, 4, 5, or 6 cards, got {}", cards.len()));
+    }
+    Ok(cards)
+}
+
+/// Format card bytes as "AhKd7c", failing on values >= 52 or duplicate cards
+#[wasm_bindgen(js_name = formatCards)]
+pub fn format_cards(cards: &[u8]) -> Result<String, String> {
+    validate_cards(cards)?;
+    Ok(cards.iter().map(|&card| card_to_string(card)).collect())
+}
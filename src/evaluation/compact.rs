@@ -0,0 +1,202 @@
+// Low memory evaluator backend
+//
+// Flushes are looked up by the 13-bit rank mask of the flush suit. Everything else only depends
+// on how many cards of each rank there are, so those hands are looked up by a perfect hash of
+// the rank counts (one table per hand size). ~160KB of tables built on first use, and ranks are
+// identical to the TwoPlusTwo table
+
+use lazy_static::lazy_static;
+
+use super::cards::validate_cards;
+use super::evaluator::HandEvaluator;
+use super::generator::score_hand;
+
+/// Number of rank count vectors (13 ranks, 0-4 cards each) summing to 0..=7
+const fn count_vectors() -> [[u32; 8]; 14] {
+    // table[n][s]: ways for n ranks to hold s cards
+    let mut table = [[0u32; 8]; 14];
+    table[0][0] = 1;
+    let mut n = 1;
+    while n < 14 {
+        let mut s = 0;
+        while s < 8 {
+            let mut v = 0;
+            while v <= 4 && v <= s {
+                table[n][s] += table[n - 1][s - v];
+                v += 1;
+            }
+            s += 1;
+        }
+        n += 1;
+    }
+    table
+}
+
+const COUNT_VECTORS: [[u32; 8]; 14] = count_vectors();
+
+/// HASH_OFFSETS[rank][cards left][count]: hash contribution of `count` cards at `rank`
+const HASH_OFFSETS: [[[u32; 5]; 8]; 13] = {
+    let mut table = [[[0u32; 5]; 8]; 13];
+    let mut rank = 0;
+    while rank < 13 {
+        let rest = 12 - rank;
+        let mut left = 0;
+        while left < 8 {
+            let mut count = 1;
+            while count <= 4 {
+                let below = count - 1;
+                table[rank][left][count] = table[rank][left][below]
+                    + if below <= left { COUNT_VECTORS[rest][left - below] } else { 0 };
+                count += 1;
+            }
+            left += 1;
+        }
+        rank += 1;
+    }
+    table
+};
+
+#[inline]
+fn hash_counts(counts: &[u8; 13], num_cards: usize) -> usize {
+    let mut left = num_cards;
+    let mut hash = 0;
+    for (rank, &count) in counts.iter().enumerate() {
+        hash += HASH_OFFSETS[rank][left][count as usize];
+        left -= count as usize;
+        if left == 0 {
+            break;
+        }
+    }
+    hash as usize
+}
+
+struct CompactTables {
+    flush: Vec<u16>,
+    // indexed by number of cards - 5
+    unsuited: [Vec<u16>; 3],
+}
+
+lazy_static! {
+    static ref TABLES: CompactTables = build_tables();
+}
+
+fn fill_unsuited(table: &mut Vec<u16>, counts: &mut [u8; 13], rank: usize, left: usize, num_cards: usize) {
+    if rank == 13 {
+        if left == 0 {
+            table[hash_counts(counts, num_cards)] = score_hand(counts, 0) as u16;
+        }
+        return;
+    }
+    for count in 0..=left.min(4) {
+        counts[rank] = count as u8;
+        fill_unsuited(table, counts, rank + 1, left - count, num_cards);
+    }
+    counts[rank] = 0;
+}
+
+fn build_tables() -> CompactTables {
+    let mut flush = vec![0u16; 1 << 13];
+    for (mask, rank) in flush.iter_mut().enumerate() {
+        if (mask as u16).count_ones() < 5 {
+            continue;
+        }
+        let mut counts = [0u8; 13];
+        for (r, count) in counts.iter_mut().enumerate() {
+            *count = ((mask >> r) & 1) as u8;
+        }
+        *rank = score_hand(&counts, mask as u16) as u16;
+    }
+
+    let unsuited = [5, 6, 7].map(|num_cards| {
+        let mut table = vec![0u16; COUNT_VECTORS[13][num_cards] as usize];
+        fill_unsuited(&mut table, &mut [0u8; 13], 0, num_cards, num_cards);
+        table
+    });
+
+    CompactTables { flush, unsuited }
+}
+
+/// Rank counts and per-suit rank masks of a set of cards
+#[derive(Clone, Copy)]
+struct CardCounts {
+    counts: [u8; 13],
+    suits: [u16; 4],
+    num_cards: usize,
+}
+
+impl CardCounts {
+    #[inline]
+    fn new(cards: &[u8]) -> Self {
+        let mut out = CardCounts { counts: [0; 13], suits: [0; 4], num_cards: 0 };
+        out.add(cards);
+        out
+    }
+
+    #[inline]
+    fn add(&mut self, cards: &[u8]) {
+        for &card in cards {
+            self.counts[(card >> 2) as usize] += 1;
+            self.suits[(card & 3) as usize] |= 1 << (card >> 2);
+        }
+        self.num_cards += cards.len();
+    }
+
+    #[inline]
+    fn rank(&self, tables: &CompactTables) -> i32 {
+        for &mask in &self.suits {
+            if mask.count_ones() >= 5 {
+                return tables.flush[mask as usize] as i32;
+            }
+        }
+        tables.unsuited[self.num_cards - 5][hash_counts(&self.counts, self.num_cards)] as i32
+    }
+}
+
+/// Mask of `cards` added to `used`, checking in debug builds that they are 0-51 and not already used
+/// Out of range or repeated cards index past the count tables
+#[inline]
+fn debug_card_mask(cards: &[u8], used: u64) -> u64 {
+    if !cfg!(debug_assertions) {
+        return 0;
+    }
+    let mask = validate_cards(cards).unwrap_or_else(|err| panic!("{err}"));
+    assert!(mask & used == 0, "Hand and board share a card");
+    mask | used
+}
+
+/// Perfect hash evaluator needing ~160KB of tables instead of the 130MB TwoPlusTwo table
+/// Produces exactly the same ranks as the TwoPlusTwo table
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CompactEvaluator;
+
+impl CompactEvaluator {
+    pub fn new() -> Self {
+        // build the tables up front rather than on the first evaluation
+        lazy_static::initialize(&TABLES);
+        CompactEvaluator
+    }
+}
+
+impl HandEvaluator for CompactEvaluator {
+    #[inline]
+    fn board_eval<'a>(&'a self, board: &'a [u8]) -> impl Fn(&[u8]) -> i32 + 'a {
+        let tables: &CompactTables = &TABLES;
+        let board_counts = CardCounts::new(board);
+        let board_mask = debug_card_mask(board, 0);
+
+        move |hand: &[u8]| {
+            debug_assert!((5..=7).contains(&(board.len() + hand.len())), "must have 5-7 cards");
+            debug_card_mask(hand, board_mask);
+            let mut counts = board_counts;
+            counts.add(hand);
+            counts.rank(tables)
+        }
+    }
+
+    #[inline]
+    fn eval(&self, cards: &[u8]) -> i32 {
+        debug_assert!((5..=7).contains(&cards.len()), "must have 5-7 cards");
+        debug_card_mask(cards, 0);
+        CardCounts::new(cards).rank(&TABLES)
+    }
+}
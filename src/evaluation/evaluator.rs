@@ -1,3 +1,8 @@
+use crate::types::BestHand;
+use super::cards::validate_cards;
+use super::generator::generate_hand_ranks;
+use super::validation::{validate_hand_ranks, RankTableError};
+
 // --- Hand Evaluation Functions ---
 
 #[inline]
@@ -44,3 +49,107 @@ pub fn gen_board_eval<'a>(ranks_data: &'a [u8], board: &'a [u8]) -> impl Fn(&[u8
         }
     }
 }
+
+/// Hand evaluator backend used by the equity code
+///
+/// Ranks follow the 2+2 format: higher is better and equal ranks tie, so any backend
+/// producing ranks in the same order can replace the lookup table
+pub trait HandEvaluator {
+    /// Prepares `board` and returns a function ranking board + `hand`
+    /// board + hand must be 5 to 7 cards
+    fn board_eval<'a>(&'a self, board: &'a [u8]) -> impl Fn(&[u8]) -> i32 + 'a;
+
+    /// Rank of 5 to 7 cards
+    #[inline]
+    fn eval(&self, cards: &[u8]) -> i32 {
+        let (board, hand) = cards.split_at(cards.len() - 2);
+        self.board_eval(board)(hand)
+    }
+}
+
+/// Raw HandRanks.dat bytes
+impl HandEvaluator for [u8] {
+    #[inline]
+    fn board_eval<'a>(&'a self, board: &'a [u8]) -> impl Fn(&[u8]) -> i32 + 'a {
+        gen_board_eval(self, board)
+    }
+}
+
+/// TwoPlusTwo lookup table evaluator, the default backend
+#[derive(Debug, Clone, PartialEq)]
+pub struct TwoPlusTwoEvaluator {
+    data: Vec<u8>,
+}
+
+impl TwoPlusTwoEvaluator {
+    /// Wrap HandRanks.dat bytes without checking them
+    pub fn new(data: Vec<u8>) -> Self {
+        Self { data }
+    }
+
+    /// Wrap HandRanks.dat bytes after `validate_hand_ranks`
+    pub fn try_new(data: Vec<u8>) -> Result<Self, RankTableError> {
+        validate_hand_ranks(&data)?;
+        Ok(Self::new(data))
+    }
+
+    /// Build the table in memory with `generate_hand_ranks`
+    pub fn generated() -> Self {
+        Self::new(generate_hand_ranks())
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl HandEvaluator for TwoPlusTwoEvaluator {
+    #[inline]
+    fn board_eval<'a>(&'a self, board: &'a [u8]) -> impl Fn(&[u8]) -> i32 + 'a {
+        gen_board_eval(&self.data, board)
+    }
+}
+
+/// Best 5 of 5-7 distinct cards, keeping the input order of the chosen cards
+pub fn best_five_cards<E: HandEvaluator + ?Sized>(evaluator: &E, cards: &[u8]) -> Result<BestHand, String> {
+    if cards.len() < 5 || cards.len() > 7 {
+        return Err(format!("Need 5-7 cards, got {}", cards.len()));
+    }
+    validate_cards(cards)?;
+
+    let n = cards.len();
+    let mut best = BestHand { cards: [0; 5], rank: i32::MIN };
+    for a in 0..n {
+        for b in (a + 1)..n {
+            for c in (b + 1)..n {
+                for d in (c + 1)..n {
+                    for e in (d + 1)..n {
+                        let five = [cards[a], cards[b], cards[c], cards[d], cards[e]];
+                        let rank = evaluator.eval(&five);
+                        if rank > best.rank {
+                            best = BestHand { cards: five, rank };
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(best)
+}
+
+/// Best five cards for a Hold'em hand on a 3-5 card board
+pub fn best_holdem_hand<E: HandEvaluator + ?Sized>(
+    evaluator: &E,
+    hand: &[u8; 2],
+    board: &[u8],
+) -> Result<BestHand, String> {
+    if board.len() < 3 || board.len() > 5 {
+        return Err("Board must have 3, 4, or 5 cards".to_string());
+    }
+
+    let mut cards = [0u8; 7];
+    cards[..2].copy_from_slice(hand);
+    cards[2..2 + board.len()].copy_from_slice(board);
+    best_five_cards(evaluator, &cards[..2 + board.len()])
+}
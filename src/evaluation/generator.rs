@@ -0,0 +1,326 @@
+// Generator for the TwoPlusTwo hand-rank table (HandRanks.dat)
+//
+// Port of the classic 2+2 generate_table algorithm. Every reachable set of up to 6 cards gets
+// a 53-entry state block, suits that can no longer make a flush are collapsed, and the 7th card
+// (or slot 0 of a 5/6 card state) holds the final rank: (category << 12) | rank within category
+
+/// Number of u32 entries in the table
+pub const HAND_RANKS_ENTRIES: usize = 32_487_834;
+
+/// Size in bytes of the table, matches HandRanks.dat
+pub const HAND_RANKS_LEN: usize = HAND_RANKS_ENTRIES * 4;
+
+const BINOMIAL: [[u32; 6]; 14] = {
+    let mut table = [[0u32; 6]; 14];
+    let mut n = 0;
+    while n < 14 {
+        table[n][0] = 1;
+        let mut k = 1;
+        while k < 6 && k <= n {
+            table[n][k] = table[n - 1][k - 1] + table[n - 1][k];
+            k += 1;
+        }
+        n += 1;
+    }
+    table
+};
+
+// Every 5-rank mask that forms a straight, wheel first
+const STRAIGHT_MASKS: [u16; 10] = [
+    0x100F, 0x001F, 0x003E, 0x007C, 0x00F8, 0x01F0, 0x03E0, 0x07C0, 0x0F80, 0x1F00,
+];
+
+/// Returns the top rank (0 = deuce, 12 = ace) of the best straight in a rank mask
+/// The wheel (A-2-3-4-5) is reported as 3 (five high)
+#[inline]
+pub(crate) fn straight_top(mask: u16) -> Option<u32> {
+    for top in (4..13).rev() {
+        if (mask >> (top - 4)) & 0x1F == 0x1F {
+            return Some(top);
+        }
+    }
+    if mask & 0x100F == 0x100F {
+        return Some(3);
+    }
+    None
+}
+
+/// Keeps only the `n` highest set bits of a rank mask
+#[inline]
+fn top_ranks(mut mask: u16, n: u32) -> u16 {
+    while mask.count_ones() > n {
+        mask &= mask - 1;
+    }
+    mask
+}
+
+#[inline]
+fn highest_rank(mask: u16) -> u32 {
+    15 - mask.leading_zeros()
+}
+
+/// Index of a rank set among all sets of the same size ordered by strength
+#[inline]
+fn colex(mut mask: u16) -> u32 {
+    let mut index = 0;
+    let mut i = 1;
+    while mask != 0 {
+        let rank = mask.trailing_zeros() as usize;
+        index += BINOMIAL[rank][i];
+        mask &= mask - 1;
+        i += 1;
+    }
+    index
+}
+
+/// Removes the ranks in `removed` from `mask`, shifting the higher ranks down
+#[inline]
+fn squeeze(mask: u16, removed: u16) -> u16 {
+    let mut out = 0u16;
+    let mut bit = 0;
+    for rank in 0..13 {
+        if removed & (1 << rank) != 0 {
+            continue;
+        }
+        if mask & (1 << rank) != 0 {
+            out |= 1 << bit;
+        }
+        bit += 1;
+    }
+    out
+}
+
+/// Ordinal of 5 distinct non-straight ranks, 1 = 7-5-4-3-2
+#[inline]
+fn no_pair_ordinal(mask: u16) -> u32 {
+    let below = STRAIGHT_MASKS.iter().filter(|&&s| s < mask).count() as u32;
+    colex(mask) - below + 1
+}
+
+/// Scores a 5 to 7 card hand in the 2+2 format: (category << 12) | rank within category
+///
+/// `counts` holds how many cards of each rank (0 = deuce) are in the hand and `flush_mask`
+/// is the rank mask of the suit with at least 5 cards, or 0 if there is none
+pub(crate) fn score_hand(counts: &[u8; 13], flush_mask: u16) -> u32 {
+    let mut ranks = 0u16;
+    let mut pairs = 0u16;
+    let mut trips = 0u16;
+    let mut quads = 0u16;
+    for (rank, &count) in counts.iter().enumerate() {
+        let bit = 1u16 << rank;
+        if count >= 1 { ranks |= bit; }
+        if count >= 2 { pairs |= bit; }
+        if count >= 3 { trips |= bit; }
+        if count >= 4 { quads |= bit; }
+    }
+
+    if let Some(top) = straight_top(flush_mask) {
+        return (9 << 12) | (top - 2);
+    }
+
+    if quads != 0 {
+        let quad = highest_rank(quads);
+        let kicker = highest_rank(ranks & !(1 << quad));
+        let kicker = kicker - (kicker > quad) as u32;
+        return (8 << 12) | (quad * 12 + kicker + 1);
+    }
+
+    if trips != 0 {
+        let trip = highest_rank(trips);
+        let others = pairs & !(1 << trip);
+        if others != 0 {
+            let pair = highest_rank(others);
+            let pair = pair - (pair > trip) as u32;
+            return (7 << 12) | (trip * 12 + pair + 1);
+        }
+    }
+
+    if flush_mask.count_ones() >= 5 {
+        return (6 << 12) | no_pair_ordinal(top_ranks(flush_mask, 5));
+    }
+
+    if let Some(top) = straight_top(ranks) {
+        return (5 << 12) | (top - 2);
+    }
+
+    if trips != 0 {
+        let trip = highest_rank(trips);
+        let kickers = top_ranks(ranks & !(1 << trip), 2);
+        return (4 << 12) | (trip * 66 + colex(squeeze(kickers, 1 << trip)) + 1);
+    }
+
+    if pairs.count_ones() >= 2 {
+        let high = highest_rank(pairs);
+        let low = highest_rank(pairs & !(1 << high));
+        let used = (1u16 << high) | (1 << low);
+        let kicker = highest_rank(ranks & !used);
+        let kicker = kicker - (kicker > low) as u32 - (kicker > high) as u32;
+        return (3 << 12) | ((BINOMIAL[high as usize][2] + low) * 11 + kicker + 1);
+    }
+
+    if pairs != 0 {
+        let pair = highest_rank(pairs);
+        let kickers = top_ranks(ranks & !(1 << pair), 3);
+        return (2 << 12) | (pair * 220 + colex(squeeze(kickers, 1 << pair)) + 1);
+    }
+
+    (1 << 12) | no_pair_ordinal(top_ranks(ranks, 5))
+}
+
+/// Scores 5 to 7 cards like score_hand, slower than a table lookup but without any tables
+pub(crate) fn score_cards(cards: &[u8]) -> u32 {
+    let mut counts = [0u8; 13];
+    let mut suit_masks = [0u16; 4];
+    for &card in cards {
+        counts[(card >> 2) as usize] += 1;
+        suit_masks[(card & 3) as usize] |= 1 << (card >> 2);
+    }
+
+    let flush_mask = suit_masks
+        .iter()
+        .copied()
+        .find(|mask| mask.count_ones() >= 5)
+        .unwrap_or(0);
+
+    score_hand(&counts, flush_mask)
+}
+
+// --- Table generation ---
+
+/// Adds a card (0-51) to a state id. Ids pack up to 7 cards as ((rank + 1) << 4) | suit
+/// sorted descending, where suit 0 means the suit can no longer matter for a flush.
+/// Returns the new id and card count, id 0 if the card can't be added
+fn make_id(id_in: u64, new_card: u8) -> (u64, usize) {
+    let mut suit_count = [0u8; 5];
+    let mut rank_count = [0u8; 14];
+    let mut wk = [0u8; 8];
+    for card_num in 0..6 {
+        wk[card_num + 1] = ((id_in >> (8 * card_num)) & 0xff) as u8;
+    }
+    wk[0] = (((new_card >> 2) + 1) << 4) + (new_card & 3) + 1;
+
+    let mut duplicate = false;
+    let mut num_cards = 0;
+    while wk[num_cards] != 0 {
+        suit_count[(wk[num_cards] & 0xf) as usize] += 1;
+        rank_count[((wk[num_cards] >> 4) & 0xf) as usize] += 1;
+        if num_cards > 0 && wk[0] == wk[num_cards] {
+            duplicate = true;
+        }
+        num_cards += 1;
+    }
+    if duplicate {
+        return (0, num_cards);
+    }
+
+    if num_cards > 4 && rank_count.iter().any(|&count| count > 4) {
+        return (0, num_cards);
+    }
+
+    // a suit can only still make a flush if it has at least num_cards - 2 cards
+    let need_suited = num_cards as i32 - 2;
+    if need_suited > 1 {
+        for card in wk.iter_mut().take(num_cards) {
+            if (suit_count[(*card & 0xf) as usize] as i32) < need_suited {
+                *card &= 0xf0;
+            }
+        }
+    }
+
+    wk[..7].sort_unstable_by(|a, b| b.cmp(a));
+
+    let id = wk[..7]
+        .iter()
+        .enumerate()
+        .fold(0u64, |id, (i, &card)| id | ((card as u64) << (8 * i)));
+    (id, num_cards)
+}
+
+/// Scores a 5 to 7 card state id, 0 for the invalid id
+fn eval_id(id: u64) -> u32 {
+    if id == 0 {
+        return 0;
+    }
+
+    let mut counts = [0u8; 13];
+    let mut suit_masks = [0u16; 4];
+    for card_num in 0..7 {
+        let card = ((id >> (8 * card_num)) & 0xff) as u8;
+        if card == 0 {
+            break;
+        }
+        let rank = ((card >> 4) - 1) as usize;
+        counts[rank] += 1;
+        let suit = card & 0xf;
+        if suit != 0 {
+            suit_masks[(suit - 1) as usize] |= 1 << rank;
+        }
+    }
+
+    let flush_mask = suit_masks
+        .iter()
+        .copied()
+        .find(|mask| mask.count_ones() >= 5)
+        .unwrap_or(0);
+
+    score_hand(&counts, flush_mask)
+}
+
+/// Builds the TwoPlusTwo hand-rank table, byte for byte the same layout as HandRanks.dat
+/// Takes a few seconds in release builds and allocates ~130MB
+pub fn generate_hand_ranks() -> Vec<u8> {
+    // collect every reachable id of up to 6 cards, ids with more cards always sort higher
+    let mut ids: Vec<u64> = vec![0];
+    let mut level: Vec<u64> = vec![0];
+    for _ in 0..6 {
+        let mut next = Vec::with_capacity(level.len() * 52);
+        for &id in &level {
+            for card in 0..52u8 {
+                let (new_id, _) = make_id(id, card);
+                if new_id != 0 {
+                    next.push(new_id);
+                }
+            }
+        }
+        next.sort_unstable();
+        next.dedup();
+        ids.extend_from_slice(&next);
+        level = next;
+    }
+
+    let mut table = vec![0u8; ids.len() * 53 * 4 + 53 * 4];
+    let mut put = |idx: usize, value: u32| {
+        table[idx * 4..idx * 4 + 4].copy_from_slice(&value.to_le_bytes());
+    };
+
+    for (id_num, &id) in ids.iter().enumerate() {
+        let mut num_cards = 0;
+        for card in 1..53usize {
+            let (new_id, count) = make_id(id, (card - 1) as u8);
+            num_cards = count;
+            let slot = if num_cards < 7 {
+                let idx = if new_id == 0 {
+                    0
+                } else {
+                    ids.binary_search(&new_id).expect("state id was generated")
+                };
+                (idx * 53 + 53) as u32
+            } else {
+                eval_id(new_id)
+            };
+            put(id_num * 53 + card + 53, slot);
+        }
+
+        if num_cards == 6 || num_cards == 7 {
+            put(id_num * 53 + 53, eval_id(id));
+        }
+    }
+
+    table
+}
+
+/// Generates the hand-rank table and writes it to `path` in the HandRanks.dat format
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write_hand_ranks<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<()> {
+    std::fs::write(path, generate_hand_ranks())
+}
@@ -0,0 +1,225 @@
+// Decoding of evaluator ranks into hand categories and descriptions
+//
+// Both gen_board_eval (Hold'em) and the Omaha evaluator return 2+2 ranks:
+// (category << 12) | rank within category, where rank 1 is the weakest hand of the category
+
+use lazy_static::lazy_static;
+use wasm_bindgen::prelude::*;
+
+use super::generator::score_hand;
+
+/// Poker hand category, ordered from weakest to strongest
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HandCategory {
+    HighCard = 1,
+    OnePair = 2,
+    TwoPair = 3,
+    ThreeOfAKind = 4,
+    Straight = 5,
+    Flush = 6,
+    FullHouse = 7,
+    FourOfAKind = 8,
+    StraightFlush = 9,
+}
+
+/// Number of distinct hands in each category, indexed by category
+const CATEGORY_SIZES: [u16; 10] = [0, 1277, 2860, 858, 858, 10, 1277, 156, 156, 10];
+
+/// Number of distinct ranks in a hand of each category, indexed by category
+const DISTINCT_RANKS: [usize; 10] = [0, 5, 4, 3, 3, 5, 5, 2, 2, 5];
+
+const RANK_NAMES: [&str; 13] = [
+    "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten", "Jack", "Queen",
+    "King", "Ace",
+];
+
+const RANK_PLURALS: [&str; 13] = [
+    "Twos", "Threes", "Fours", "Fives", "Sixes", "Sevens", "Eights", "Nines", "Tens", "Jacks",
+    "Queens", "Kings", "Aces",
+];
+
+lazy_static! {
+    // Significant ranks (paired ranks first, then kickers, high to low) for every
+    // rank of each category, indexed by [category][rank within category - 1]
+    static ref RANK_CARDS: Vec<Vec<[u8; 5]>> = build_rank_cards();
+}
+
+fn build_rank_cards() -> Vec<Vec<[u8; 5]>> {
+    let mut table: Vec<Vec<[u8; 5]>> = CATEGORY_SIZES
+        .iter()
+        .map(|&size| vec![[0u8; 5]; size as usize])
+        .collect();
+
+    let mut store = |counts: &[u8; 13], flush_mask: u16| {
+        let rank = score_hand(counts, flush_mask);
+        let mut ranks: Vec<u8> = (0..13u8).filter(|&r| counts[r as usize] > 0).collect();
+        ranks.sort_by(|&a, &b| counts[b as usize].cmp(&counts[a as usize]).then(b.cmp(&a)));
+        let mut cards = [0u8; 5];
+        cards[..ranks.len()].copy_from_slice(&ranks);
+        table[(rank >> 12) as usize][((rank & 0xfff) - 1) as usize] = cards;
+    };
+
+    // every multiset of 5 ranks, plus the suited version of the unpaired ones
+    for a in 0..13 {
+        for b in a..13 {
+            for c in b..13 {
+                for d in c..13 {
+                    for e in d..13 {
+                        if a == e {
+                            continue;
+                        }
+                        let mut counts = [0u8; 13];
+                        for r in [a, b, c, d, e] {
+                            counts[r] += 1;
+                        }
+                        store(&counts, 0);
+                        if counts.iter().all(|&count| count <= 1) {
+                            let mask = [a, b, c, d, e].iter().fold(0u16, |m, &r| m | (1 << r));
+                            store(&counts, mask);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    table
+}
+
+impl HandCategory {
+    /// Category of an evaluator rank, None for ranks that aren't real hands (e.g. 0)
+    pub fn from_rank(rank: i32) -> Option<Self> {
+        if rank <= 0 {
+            return None;
+        }
+        let category = (rank >> 12) as usize;
+        let within = (rank & 0xfff) as u16;
+        if !(1..=9).contains(&category) || within == 0 || within > CATEGORY_SIZES[category] {
+            return None;
+        }
+        Some(match category {
+            1 => HandCategory::HighCard,
+            2 => HandCategory::OnePair,
+            3 => HandCategory::TwoPair,
+            4 => HandCategory::ThreeOfAKind,
+            5 => HandCategory::Straight,
+            6 => HandCategory::Flush,
+            7 => HandCategory::FullHouse,
+            8 => HandCategory::FourOfAKind,
+            _ => HandCategory::StraightFlush,
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HandCategory::HighCard => "High Card",
+            HandCategory::OnePair => "One Pair",
+            HandCategory::TwoPair => "Two Pair",
+            HandCategory::ThreeOfAKind => "Three of a Kind",
+            HandCategory::Straight => "Straight",
+            HandCategory::Flush => "Flush",
+            HandCategory::FullHouse => "Full House",
+            HandCategory::FourOfAKind => "Four of a Kind",
+            HandCategory::StraightFlush => "Straight Flush",
+        }
+    }
+
+    /// Number of distinct hands in this category
+    pub fn size(&self) -> u16 {
+        CATEGORY_SIZES[*self as usize]
+    }
+}
+
+/// Decoded evaluator rank
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandRankInfo {
+    pub(crate) rank: i32,
+    pub(crate) category: HandCategory,
+    pub(crate) rank_in_category: u16,
+    pub(crate) description: String,
+}
+
+#[wasm_bindgen]
+impl HandRankInfo {
+    /// Decode a rank from the Hold'em or Omaha evaluator
+    #[wasm_bindgen(js_name = fromRank)]
+    pub fn from_rank_wasm(rank: i32) -> Result<HandRankInfo, String> {
+        Self::from_rank(rank).ok_or_else(|| format!("Invalid hand rank: {rank}"))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn rank(&self) -> i32 {
+        self.rank
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn category(&self) -> HandCategory {
+        self.category
+    }
+
+    #[wasm_bindgen(getter, js_name = categoryName)]
+    pub fn category_name(&self) -> String {
+        self.category.name().to_string()
+    }
+
+    /// 1 for the weakest hand of the category up to the category size
+    #[wasm_bindgen(getter, js_name = rankInCategory)]
+    pub fn rank_in_category(&self) -> u16 {
+        self.rank_in_category
+    }
+
+    /// e.g. "Two Pair, Aces and Kings" or "Flush, King high"
+    #[wasm_bindgen(getter)]
+    pub fn description(&self) -> String {
+        self.description.clone()
+    }
+}
+
+// Non-WASM impl block for internal Rust use
+impl HandRankInfo {
+    pub fn from_rank(rank: i32) -> Option<Self> {
+        let category = HandCategory::from_rank(rank)?;
+        let rank_in_category = (rank & 0xfff) as u16;
+        let cards = RANK_CARDS[category as usize][(rank_in_category - 1) as usize];
+        Some(Self {
+            rank,
+            category,
+            rank_in_category,
+            description: describe(category, &cards),
+        })
+    }
+
+    /// Distinct ranks of the hand (0 = deuce, 12 = ace): grouped ranks first, then kickers
+    pub fn ranks(&self) -> &'static [u8] {
+        let ranks = &RANK_CARDS[self.category as usize][(self.rank_in_category - 1) as usize];
+        &ranks[..DISTINCT_RANKS[self.category as usize]]
+    }
+}
+
+fn describe(category: HandCategory, ranks: &[u8; 5]) -> String {
+    let name = |r: u8| RANK_NAMES[r as usize];
+    let plural = |r: u8| RANK_PLURALS[r as usize];
+    // the wheel is five high
+    let straight_high = if ranks[0] == 12 && ranks[1] == 3 { 3 } else { ranks[0] };
+
+    let detail = match category {
+        HandCategory::HighCard | HandCategory::Flush => format!("{} high", name(ranks[0])),
+        HandCategory::OnePair | HandCategory::ThreeOfAKind | HandCategory::FourOfAKind => {
+            plural(ranks[0]).to_string()
+        }
+        HandCategory::TwoPair => format!("{} and {}", plural(ranks[0]), plural(ranks[1])),
+        HandCategory::FullHouse => format!("{} full of {}", plural(ranks[0]), plural(ranks[1])),
+        HandCategory::Straight | HandCategory::StraightFlush => {
+            format!("{} high", name(straight_high))
+        }
+    };
+
+    format!("{}, {}", category.name(), detail)
+}
+
+/// Shorthand for `HandRankInfo::from_rank(rank)` returning just the description
+pub fn describe_rank(rank: i32) -> Option<String> {
+    HandRankInfo::from_rank(rank).map(|info| info.description)
+}
@@ -0,0 +1,108 @@
+// Lowball evaluation for five card hands
+//
+// Deuce-to-Seven: aces are high, straights and flushes count against you, 7-5-4-3-2 is best
+// Ace-to-Five: aces are low, straights and flushes are ignored, 5-4-3-2-A is best
+//
+// Like gen_board_eval ranks, higher is better: 1 is the worst hand and the best hand
+// gets the number of distinct hands in the game (7462 for 2-7, 6175 for A-5)
+
+use lazy_static::lazy_static;
+
+use super::cards::validate_cards;
+
+// Hand strength in the usual high hand sense, lower is a better low
+// category << 20 | grouped ranks (most cards first, then highest) packed 4 bits each
+fn high_key(ranks: &[u8; 5], flush: bool, straights: bool) -> u32 {
+    let mut counts = [0u8; 13];
+    for &rank in ranks {
+        counts[rank as usize] += 1;
+    }
+
+    let mut groups: Vec<(u8, u8)> = (0..13u8)
+        .filter(|&rank| counts[rank as usize] > 0)
+        .map(|rank| (counts[rank as usize], rank))
+        .collect();
+    groups.sort_unstable_by(|a, b| b.cmp(a));
+
+    let packed = groups.iter().fold(0u32, |key, &(_, rank)| (key << 4) | rank as u32)
+        << (4 * (5 - groups.len()));
+
+    let straight = straights && groups.len() == 5 && groups[0].1 - groups[4].1 == 4;
+
+    let category = match (groups[0].0, groups.get(1).map(|g| g.0)) {
+        (4, _) => 8,
+        (3, Some(2)) => 7,
+        _ if flush && straight => 9,
+        _ if flush => 6,
+        _ if straight => 5,
+        (3, _) => 4,
+        (2, Some(2)) => 3,
+        (2, _) => 2,
+        _ => 1,
+    };
+
+    (category << 20) | packed
+}
+
+fn deuce_to_seven_key(cards: &[u8; 5]) -> u32 {
+    let ranks = cards.map(|card| card / 4);
+    let flush = cards.iter().all(|&card| card % 4 == cards[0] % 4);
+    high_key(&ranks, flush, true)
+}
+
+fn ace_to_five_key(cards: &[u8; 5]) -> u32 {
+    // ace becomes the lowest rank
+    let ranks = cards.map(|card| (card / 4 + 1) % 13);
+    high_key(&ranks, false, false)
+}
+
+/// Every distinct key of a game sorted from best low to worst
+fn sorted_keys(key: fn(&[u8; 5]) -> u32, suited: bool) -> Vec<u32> {
+    let mut keys = Vec::new();
+    for a in 0..13u8 {
+        for b in a..13 {
+            for c in b..13 {
+                for d in c..13 {
+                    for e in d..13 {
+                        if a == e {
+                            continue;
+                        }
+                        // mixed suits, plus the flush when the ranks are distinct
+                        keys.push(key(&[a * 4, b * 4 + 1, c * 4 + 2, d * 4 + 3, e * 4]));
+                        if suited && a != b && b != c && c != d && d != e {
+                            keys.push(key(&[a, b, c, d, e].map(|rank| rank * 4)));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    keys.sort_unstable();
+    keys.dedup();
+    keys
+}
+
+lazy_static! {
+    static ref DEUCE_TO_SEVEN_KEYS: Vec<u32> = sorted_keys(deuce_to_seven_key, true);
+    static ref ACE_TO_FIVE_KEYS: Vec<u32> = sorted_keys(ace_to_five_key, false);
+}
+
+#[inline]
+fn rank_of(keys: &[u32], key: u32) -> i32 {
+    let idx = keys.binary_search(&key).expect("every five card hand has a key");
+    (keys.len() - idx) as i32
+}
+
+/// Deuce-to-Seven lowball rank of a five card hand, higher is better
+/// Fails on invalid or repeated cards
+pub fn eval_deuce_to_seven(cards: &[u8; 5]) -> Result<i32, String> {
+    validate_cards(cards)?;
+    Ok(rank_of(&DEUCE_TO_SEVEN_KEYS, deuce_to_seven_key(cards)))
+}
+
+/// Ace-to-Five lowball rank of a five card hand, higher is better
+/// Fails on invalid or repeated cards
+pub fn eval_ace_to_five(cards: &[u8; 5]) -> Result<i32, String> {
+    validate_cards(cards)?;
+    Ok(rank_of(&ACE_TO_FIVE_KEYS, ace_to_five_key(cards)))
+}
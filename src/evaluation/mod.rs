@@ -1,6 +1,22 @@
 mod cards;
+#[cfg(feature = "compact")]
+mod compact;
 mod evaluator;
+mod generator;
+mod hand_rank;
+mod lowball;
+mod short_deck;
+mod typed;
+mod validation;
 pub mod combinations;
 
 pub use cards::*;
+#[cfg(feature = "compact")]
+pub use compact::*;
 pub use evaluator::*;
+pub use generator::*;
+pub use hand_rank::*;
+pub use lowball::*;
+pub use short_deck::*;
+pub use typed::*;
+pub use validation::*;
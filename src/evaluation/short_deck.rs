@@ -0,0 +1,126 @@
+// Short Deck (6+) Hold'em evaluation
+//
+// 36 card deck (six through ace), a flush beats a full house and A-6-7-8-9 is the lowest
+// straight. Ranks use the 2+2 layout with categories in short deck order: 9 straight flush,
+// 8 quads, 7 flush, 6 full house, 5 straight, then trips down to high card as usual
+
+use super::evaluator::HandEvaluator;
+use super::generator::{score_hand, straight_top};
+use super::hand_rank::HandCategory;
+
+/// Rank of the lowest short deck card (0 = deuce), the six
+pub const SHORT_DECK_LOWEST_RANK: u8 = 4;
+
+/// Mask of the 36 cards in a short deck
+pub const SHORT_DECK_MASK: u64 = ((1u64 << 52) - 1) & !((1u64 << (SHORT_DECK_LOWEST_RANK * 4)) - 1);
+
+const SHORT_DECK_CATEGORY_FLUSH: u32 = 7;
+const SHORT_DECK_CATEGORY_FULL_HOUSE: u32 = 6;
+
+#[inline]
+pub fn is_short_deck_card(card: u8) -> bool {
+    card < 52 && card / 4 >= SHORT_DECK_LOWEST_RANK
+}
+
+/// Like straight_top, with A-6-7-8-9 taking the place of the wheel as the lowest straight
+#[inline]
+fn short_deck_straight_top(mask: u16) -> Option<u32> {
+    match straight_top(mask) {
+        Some(top) => Some(top),
+        None if mask & 0x10F0 == 0x10F0 => Some(3),
+        None => None,
+    }
+}
+
+/// Scores a 5 to 7 card short deck hand, see the module comment for the category order
+pub(crate) fn score_short_deck(counts: &[u8; 13], flush_mask: u16) -> u32 {
+    if let Some(top) = short_deck_straight_top(flush_mask) {
+        return (9 << 12) | (top - 2);
+    }
+
+    let standard = score_hand(counts, 0);
+    let category = standard >> 12;
+    if category == 8 {
+        return standard;
+    }
+
+    if flush_mask.count_ones() >= 5 {
+        let mut flush_counts = [0u8; 13];
+        for (rank, count) in flush_counts.iter_mut().enumerate() {
+            *count = ((flush_mask >> rank) & 1) as u8;
+        }
+        let flush = score_hand(&flush_counts, flush_mask) & 0xfff;
+        return (SHORT_DECK_CATEGORY_FLUSH << 12) | flush;
+    }
+
+    if category == 7 {
+        return (SHORT_DECK_CATEGORY_FULL_HOUSE << 12) | (standard & 0xfff);
+    }
+    if category == 5 {
+        return standard;
+    }
+
+    let mut ranks = 0u16;
+    for (rank, &count) in counts.iter().enumerate() {
+        if count > 0 {
+            ranks |= 1 << rank;
+        }
+    }
+    if let Some(top) = short_deck_straight_top(ranks) {
+        return (5 << 12) | (top - 2);
+    }
+
+    standard
+}
+
+#[inline]
+fn score_cards(counts: &mut [u8; 13], suits: &mut [u16; 4], cards: &[u8]) -> u32 {
+    for &card in cards {
+        counts[(card >> 2) as usize] += 1;
+        suits[(card & 3) as usize] |= 1 << (card >> 2);
+    }
+    let flush_mask = suits.iter().copied().find(|mask| mask.count_ones() >= 5).unwrap_or(0);
+    score_short_deck(counts, flush_mask)
+}
+
+/// Short Deck evaluator, needs no tables
+/// Ranks compare like gen_board_eval ranks: higher is better
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ShortDeckEvaluator;
+
+impl HandEvaluator for ShortDeckEvaluator {
+    #[inline]
+    fn board_eval<'a>(&'a self, board: &'a [u8]) -> impl Fn(&[u8]) -> i32 + 'a {
+        let mut board_counts = [0u8; 13];
+        let mut board_suits = [0u16; 4];
+        for &card in board {
+            board_counts[(card >> 2) as usize] += 1;
+            board_suits[(card & 3) as usize] |= 1 << (card >> 2);
+        }
+
+        move |hand: &[u8]| {
+            let mut counts = board_counts;
+            let mut suits = board_suits;
+            score_cards(&mut counts, &mut suits, hand) as i32
+        }
+    }
+
+    #[inline]
+    fn eval(&self, cards: &[u8]) -> i32 {
+        score_cards(&mut [0u8; 13], &mut [0u16; 4], cards) as i32
+    }
+}
+
+impl HandCategory {
+    /// Category of a ShortDeckEvaluator rank
+    pub fn from_short_deck_rank(rank: i32) -> Option<Self> {
+        if rank <= 0 || rank & 0xfff == 0 {
+            return None;
+        }
+        match (rank >> 12) as u32 {
+            SHORT_DECK_CATEGORY_FLUSH => Some(HandCategory::Flush),
+            SHORT_DECK_CATEGORY_FULL_HOUSE => Some(HandCategory::FullHouse),
+            category => HandCategory::from_rank(((category << 12) | 1) as i32),
+        }
+    }
+}
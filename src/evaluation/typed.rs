@@ -0,0 +1,301 @@
+// Validated card types for the Rust API
+//
+// The rest of the crate works on raw card bytes (rank * 4 + suit). These wrappers can only be
+// built from valid, distinct cards, and hand out the bytes via as_bytes / AsRef<[u8]> so they
+// can be passed straight to the byte based functions
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::cards::{card_to_string, cards_to_mask, read_cards, string_to_card, RANKS, SUITS};
+use crate::range::HoldemRange;
+
+/// Reasons cards were rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CardError {
+    /// A card value of 52 or more
+    OutOfRange(u8),
+    /// Text that isn't a card
+    Invalid(String),
+    /// The same card appears twice
+    Duplicate(Card),
+    /// Too many or too few cards for the type
+    WrongCount { expected: &'static str, actual: usize },
+}
+
+impl fmt::Display for CardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CardError::OutOfRange(card) => write!(f, "Invalid card {card}, cards must be 0-51"),
+            CardError::Invalid(message) => write!(f, "{message}"),
+            CardError::Duplicate(card) => write!(f, "Duplicate card {card}"),
+            CardError::WrongCount { expected, actual } => {
+                write!(f, "Expected {expected} cards, got {actual}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CardError {}
+
+/// Reasons the typed equity functions fail
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EquityError {
+    /// Cards that clash, e.g. a hand sharing a card with the board
+    Card(CardError),
+    /// Anything else the equity functions reject, with their message
+    Invalid(String),
+}
+
+impl fmt::Display for EquityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EquityError::Card(err) => write!(f, "{err}"),
+            EquityError::Invalid(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for EquityError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EquityError::Card(err) => Some(err),
+            EquityError::Invalid(_) => None,
+        }
+    }
+}
+
+impl From<CardError> for EquityError {
+    fn from(err: CardError) -> Self {
+        EquityError::Card(err)
+    }
+}
+
+impl From<String> for EquityError {
+    fn from(message: String) -> Self {
+        EquityError::Invalid(message)
+    }
+}
+
+/// Checks every card is 0-51 and distinct
+fn check_cards(cards: &[u8]) -> Result<(), CardError> {
+    let mut mask = 0u64;
+    for &card in cards {
+        let card = Card::new(card)?;
+        if mask & card.mask() != 0 {
+            return Err(CardError::Duplicate(card));
+        }
+        mask |= card.mask();
+    }
+    Ok(())
+}
+
+fn check_count(cards: &[u8], sizes: &[usize], expected: &'static str) -> Result<(), CardError> {
+    if sizes.contains(&cards.len()) {
+        Ok(())
+    } else {
+        Err(CardError::WrongCount { expected, actual: cards.len() })
+    }
+}
+
+fn write_cards(f: &mut fmt::Formatter<'_>, cards: &[u8]) -> fmt::Result {
+    cards.iter().try_for_each(|&card| write!(f, "{}", card_to_string(card)))
+}
+
+/// A single card, always 0-51
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Card(u8);
+
+impl Card {
+    pub fn new(value: u8) -> Result<Self, CardError> {
+        if value < 52 {
+            Ok(Card(value))
+        } else {
+            Err(CardError::OutOfRange(value))
+        }
+    }
+
+    /// Rank 0 = deuce .. 12 = ace, suit 0-3 in "cdhs" order
+    pub fn from_rank_suit(rank: u8, suit: u8) -> Result<Self, CardError> {
+        if rank >= 13 || suit >= 4 {
+            return Err(CardError::Invalid(format!("Invalid rank {rank} or suit {suit}")));
+        }
+        Ok(Card(rank * 4 + suit))
+    }
+
+    pub fn value(self) -> u8 {
+        self.0
+    }
+
+    pub fn rank(self) -> u8 {
+        self.0 / 4
+    }
+
+    pub fn suit(self) -> u8 {
+        self.0 % 4
+    }
+
+    pub fn mask(self) -> u64 {
+        1u64 << self.0
+    }
+}
+
+impl TryFrom<u8> for Card {
+    type Error = CardError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Card::new(value)
+    }
+}
+
+impl From<Card> for u8 {
+    fn from(card: Card) -> u8 {
+        card.0
+    }
+}
+
+impl FromStr for Card {
+    type Err = CardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.trim().chars();
+        let card = match (chars.next(), chars.next(), chars.next()) {
+            (Some(rank), Some(suit), None) => {
+                string_to_card(&format!("{}{}", rank.to_ascii_uppercase(), suit.to_ascii_lowercase()))
+            }
+            _ => None,
+        };
+        card.map(Card).ok_or_else(|| CardError::Invalid(format!("Invalid card '{s}'")))
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rank = RANKS.as_bytes()[self.rank() as usize] as char;
+        let suit = SUITS.as_bytes()[self.suit() as usize] as char;
+        write!(f, "{rank}{suit}")
+    }
+}
+
+/// Two distinct hole cards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HoldemHand([u8; 2]);
+
+impl HoldemHand {
+    pub fn new(first: Card, second: Card) -> Result<Self, CardError> {
+        Self::from_bytes(&[first.0, second.0])
+    }
+
+    pub fn from_bytes(cards: &[u8]) -> Result<Self, CardError> {
+        check_count(cards, &[2], "2")?;
+        check_cards(cards)?;
+        Ok(HoldemHand([cards[0], cards[1]]))
+    }
+
+    pub fn cards(&self) -> [Card; 2] {
+        self.0.map(Card)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 2] {
+        &self.0
+    }
+
+    pub fn mask(&self) -> u64 {
+        cards_to_mask(&self.0)
+    }
+
+    /// Index into HoldemRange weights
+    pub fn hand_idx(&self) -> usize {
+        HoldemRange::get_hand_idx(self.0)
+    }
+}
+
+/// 4, 5, or 6 distinct hole cards
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OmahaHand(Vec<u8>);
+
+impl OmahaHand {
+    pub fn new(cards: &[Card]) -> Result<Self, CardError> {
+        Self::from_bytes(&cards.iter().map(|card| card.0).collect::<Vec<_>>())
+    }
+
+    pub fn from_bytes(cards: &[u8]) -> Result<Self, CardError> {
+        check_count(cards, &[4, 5, 6], "4, 5, or 6")?;
+        check_cards(cards)?;
+        Ok(OmahaHand(cards.to_vec()))
+    }
+
+    pub fn cards(&self) -> Vec<Card> {
+        self.0.iter().map(|&card| Card(card)).collect()
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn mask(&self) -> u64 {
+        cards_to_mask(&self.0)
+    }
+}
+
+/// A flop, turn, or river board of 3, 4, or 5 distinct cards
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Board(Vec<u8>);
+
+impl Board {
+    pub fn new(cards: &[Card]) -> Result<Self, CardError> {
+        Self::from_bytes(&cards.iter().map(|card| card.0).collect::<Vec<_>>())
+    }
+
+    pub fn from_bytes(cards: &[u8]) -> Result<Self, CardError> {
+        check_count(cards, &[3, 4, 5], "3, 4, or 5")?;
+        check_cards(cards)?;
+        Ok(Board(cards.to_vec()))
+    }
+
+    pub fn cards(&self) -> Vec<Card> {
+        self.0.iter().map(|&card| Card(card)).collect()
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn mask(&self) -> u64 {
+        cards_to_mask(&self.0)
+    }
+
+    /// Fails if any of the hole cards are on the board
+    pub fn check_hand(&self, hand: &[u8]) -> Result<(), CardError> {
+        match hand.iter().find(|&&card| self.mask() & (1u64 << card) != 0) {
+            Some(&card) => Err(CardError::Duplicate(Card(card))),
+            None => Ok(()),
+        }
+    }
+}
+
+macro_rules! card_list_traits {
+    ($($name:ident),*) => {$(
+        impl FromStr for $name {
+            type Err = CardError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::from_bytes(&read_cards(s).map_err(CardError::Invalid)?)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write_cards(f, &self.0)
+            }
+        }
+
+        impl AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+    )*};
+}
+
+card_list_traits!(HoldemHand, OmahaHand, Board);
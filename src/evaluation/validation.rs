@@ -0,0 +1,98 @@
+// Checks that a buffer really is the TwoPlusTwo hand-rank table before it's used
+//
+// final_p returns 0 for out of bounds reads, so a truncated or unrelated file would otherwise
+// produce plausible looking but meaningless equities
+
+use std::fmt;
+
+use super::cards::card_to_string;
+use super::evaluator::{fast_eval, final_p};
+use super::generator::HAND_RANKS_LEN;
+
+/// FNV-1a 64 hash of the full HandRanks.dat table
+pub const HAND_RANKS_FNV1A: u64 = 0x3470_527a_2937_701c;
+
+// (cards, expected rank) for a handful of 5 and 7 card hands
+const SPOT_CHECKS: [(&[u8], u32); 7] = [
+    (&[51, 47, 43, 39, 35], 36874),        // AsKsQsJsTs, royal flush
+    (&[20, 12, 8, 4, 1], 4097),            // 7c5c4c3c2d, worst high card
+    (&[49, 12, 8, 4, 0], 20481),           // Ad5c4c3c2c, wheel
+    (&[0, 1, 2, 3, 51, 50, 49], 32780),    // 2c2d2h2s AsAhAd, quad deuces ace kicker
+    (&[51, 50, 49, 47, 46, 1, 5], 28828),  // AsAhAd KsKh 2d3d, aces full of kings
+    (&[51, 47, 43, 39, 3, 6, 9], 25846),   // AsKsQsJs2s 3h4d, ace high flush
+    (&[50, 46, 40, 33, 26, 13, 4], 5364),  // AhKhQcTd8h5d3c, ace king high
+];
+
+/// Reasons a buffer was rejected as a hand-rank table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RankTableError {
+    /// The buffer isn't the size of HandRanks.dat
+    InvalidLength { expected: usize, actual: usize },
+    /// A known hand didn't evaluate to its known rank
+    WrongEvaluation { cards: Vec<u8>, expected: u32, actual: u32 },
+    /// The table has the right size and evaluates the spot checks but its contents differ
+    ChecksumMismatch { expected: u64, actual: u64 },
+}
+
+impl fmt::Display for RankTableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RankTableError::InvalidLength { expected, actual } => {
+                write!(f, "Hand rank table must be {expected} bytes, got {actual}")
+            }
+            RankTableError::WrongEvaluation { cards, expected, actual } => write!(
+                f,
+                "Hand rank table evaluates {} as {actual}, expected {expected}",
+                cards.iter().map(|&card| card_to_string(card)).collect::<String>()
+            ),
+            RankTableError::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "Hand rank table checksum is {actual:#018x}, expected {expected:#018x}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RankTableError {}
+
+/// FNV-1a 64 hash, used as the table fingerprint
+pub fn fnv1a_64(data: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &byte in data {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+/// Verify length, a set of known evaluations and the fingerprint of a hand-rank table
+pub fn validate_hand_ranks(data: &[u8]) -> Result<(), RankTableError> {
+    if data.len() != HAND_RANKS_LEN {
+        return Err(RankTableError::InvalidLength {
+            expected: HAND_RANKS_LEN,
+            actual: data.len(),
+        });
+    }
+
+    for &(cards, expected) in SPOT_CHECKS.iter() {
+        let p = fast_eval(data, cards, 53);
+        let actual = if cards.len() == 7 { p } else { final_p(data, p as usize) };
+        if actual != expected {
+            return Err(RankTableError::WrongEvaluation {
+                cards: cards.to_vec(),
+                expected,
+                actual,
+            });
+        }
+    }
+
+    let actual = fnv1a_64(data);
+    if actual != HAND_RANKS_FNV1A {
+        return Err(RankTableError::ChecksumMismatch {
+            expected: HAND_RANKS_FNV1A,
+            actual,
+        });
+    }
+
+    Ok(())
+}
@@ -12,25 +12,105 @@ pub use equity::*;
 pub use range::*;
 pub use types::*;
 
-/// Main calculator struct - holds hand evaluation data
+/// Hand evaluator held by EquityCalculator
+/// The TwoPlusTwo table, or the compact perfect hash evaluator with the `compact` feature
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum CalculatorEvaluator {
+    TwoPlusTwo(TwoPlusTwoEvaluator),
+    #[cfg(feature = "compact")]
+    Compact(CompactEvaluator),
+}
+
+impl From<TwoPlusTwoEvaluator> for CalculatorEvaluator {
+    fn from(evaluator: TwoPlusTwoEvaluator) -> Self {
+        CalculatorEvaluator::TwoPlusTwo(evaluator)
+    }
+}
+
+#[cfg(feature = "compact")]
+impl From<CompactEvaluator> for CalculatorEvaluator {
+    fn from(evaluator: CompactEvaluator) -> Self {
+        CalculatorEvaluator::Compact(evaluator)
+    }
+}
+
+/// Board evaluator of either backend
+#[cfg(feature = "compact")]
+enum EitherBoardEval<T, C> {
+    TwoPlusTwo(T),
+    Compact(C),
+}
+
+impl HandEvaluator for CalculatorEvaluator {
+    #[cfg(not(feature = "compact"))]
+    #[inline]
+    fn board_eval<'a>(&'a self, board: &'a [u8]) -> impl Fn(&[u8]) -> i32 + 'a {
+        let CalculatorEvaluator::TwoPlusTwo(evaluator) = self;
+        evaluator.board_eval(board)
+    }
+
+    #[cfg(feature = "compact")]
+    #[inline]
+    fn board_eval<'a>(&'a self, board: &'a [u8]) -> impl Fn(&[u8]) -> i32 + 'a {
+        let board_eval = match self {
+            CalculatorEvaluator::TwoPlusTwo(evaluator) => EitherBoardEval::TwoPlusTwo(evaluator.board_eval(board)),
+            CalculatorEvaluator::Compact(evaluator) => EitherBoardEval::Compact(evaluator.board_eval(board)),
+        };
+        move |hand: &[u8]| match &board_eval {
+            EitherBoardEval::TwoPlusTwo(eval) => eval(hand),
+            EitherBoardEval::Compact(eval) => eval(hand),
+        }
+    }
+
+    #[inline]
+    fn eval(&self, cards: &[u8]) -> i32 {
+        match self {
+            CalculatorEvaluator::TwoPlusTwo(evaluator) => evaluator.eval(cards),
+            #[cfg(feature = "compact")]
+            CalculatorEvaluator::Compact(evaluator) => evaluator.eval(cards),
+        }
+    }
+}
+
+/// Main calculator struct - holds the hand evaluator
+/// The equity functions in `equity` are generic over `HandEvaluator` for other backends
 #[wasm_bindgen]
 pub struct EquityCalculator {
-    hand_ranks_data: Vec<u8>,
+    evaluator: CalculatorEvaluator,
     cached_hero_range: Option<HoldemRange>,
     cached_vs_range: Option<HoldemRange>,
     cached_omaha_range: Option<OmahaRange>,
+    cached_short_deck_hero_range: Option<ShortDeckRange>,
+    cached_short_deck_vs_range: Option<ShortDeckRange>,
+    cached_multiway_ranges: Vec<HoldemRange>,
 }
 
 #[wasm_bindgen]
 impl EquityCalculator {
     #[wasm_bindgen(constructor)]
     pub fn new(data: Vec<u8>) -> Self {
-        EquityCalculator {
-            hand_ranks_data: data,
-            cached_hero_range: None,
-            cached_vs_range: None,
-            cached_omaha_range: None,
-        }
+        Self::with_evaluator(TwoPlusTwoEvaluator::new(data))
+    }
+
+    /// Uses the compact perfect hash evaluator, so no HandRanks.dat is needed
+    #[cfg(feature = "compact")]
+    pub fn compact() -> Self {
+        Self::with_evaluator(CompactEvaluator::new())
+    }
+
+    /// Like the constructor but rejects data that isn't a valid HandRanks.dat table
+    /// Checks the length, a handful of known hand evaluations and the table checksum
+    #[wasm_bindgen(js_name = tryNew)]
+    pub fn try_new_wasm(data: Vec<u8>) -> Result<EquityCalculator, String> {
+        Self::try_new(data).map_err(|err| err.to_string())
+    }
+
+    /// Build the hand-rank table in memory instead of loading HandRanks.dat
+    /// Takes a few seconds and allocates ~130MB
+    #[wasm_bindgen(js_name = withGeneratedTables)]
+    pub fn with_generated_tables() -> Self {
+        Self::new(evaluation::generate_hand_ranks())
     }
 
     /// Set the cached hero range for Holdem calculations
@@ -54,13 +134,34 @@ impl EquityCalculator {
         self.cached_omaha_range = Some(range);
     }
 
+    /// Set the cached hero range for Short Deck calculations
+    #[wasm_bindgen(js_name = setShortDeckHeroRange)]
+    pub fn set_short_deck_hero_range(&mut self, range: ShortDeckRange) {
+        self.cached_short_deck_hero_range = Some(range);
+    }
+
+    /// Set the cached villain range for Short Deck calculations
+    #[wasm_bindgen(js_name = setShortDeckVsRange)]
+    pub fn set_short_deck_vs_range(&mut self, range: ShortDeckRange) {
+        self.cached_short_deck_vs_range = Some(range);
+    }
+
+    /// Set the cached ranges for multiway Holdem calculations, one per player
+    #[wasm_bindgen(js_name = setMultiwayRanges)]
+    pub fn set_multiway_ranges(&mut self, ranges: Vec<HoldemRange>) {
+        self.cached_multiway_ranges = ranges;
+    }
+
     /// Calculate equity for each hand in hero_range vs vs_range
     /// Enumerates all possible runouts for incomplete boards (3 or 4 cards)
+    /// An empty board gives exact preflop equity
+    /// Optional dead_cards never come and block combos holding them, preflop they only block combos
     /// IMPORTANT: Call setHeroRange and setVsRange before using this method
     #[wasm_bindgen]
     pub fn equity_vs_range(
         &self,
         board: &[u8],
+        dead_cards: Option<Vec<u8>>,
     ) -> Result<Vec<EquityResult>, String> {
         let hero_range = self.cached_hero_range.as_ref()
             .ok_or("No hero range set. Call setHeroRange first.")?;
@@ -68,94 +169,287 @@ impl EquityCalculator {
             .ok_or("No villain range set. Call setVsRange first.")?;
 
         equity::holdem::calculate_equity_vs_range(
-            &self.hand_ranks_data,
+            &self.evaluator,
             hero_range,
             vs_range,
-            board
+            board,
+            dead_cards.as_deref().unwrap_or_default()
         )
     }
 
     /// Calculate leaf equity (5-card board only, no enumeration)
+    /// Optional dead_cards block combos holding them
     /// IMPORTANT: Call setHeroRange and setVsRange before using this method
     #[wasm_bindgen]
     pub fn leaf_equity_vs_range(
         &self,
         board: &[u8],
+        dead_cards: Option<Vec<u8>>,
     ) -> Result<Vec<EquityResult>, String> {
         let hero_range = self.cached_hero_range.as_ref()
             .ok_or("No hero range set. Call setHeroRange first.")?;
         let vs_range = self.cached_vs_range.as_ref()
             .ok_or("No villain range set. Call setVsRange first.")?;
+        equity::holdem::calculate_leaf_equity(
+            &self.evaluator,
+            hero_range,
+            vs_range,
+            board,
+            dead_cards.as_deref().unwrap_or_default()
+        )
+    }
+
+    /// Multiway equity for each combo of every player, ties split between the tied players
+    /// Board can be empty (preflop) or have 3, 4, or 5 cards. Small spots are enumerated exactly,
+    /// larger ones sample trials_per_combo deals and runouts per combo
+    /// Optional dead_cards never come and block combos holding them
+    /// IMPORTANT: Call setMultiwayRanges before using this method
+    #[wasm_bindgen(js_name = multiwayEquity)]
+    pub fn multiway_equity(
+        &self,
+        board: &[u8],
+        trials_per_combo: usize,
+        dead_cards: Option<Vec<u8>>,
+    ) -> Result<Vec<MultiwayEquityResult>, String> {
+        if self.cached_multiway_ranges.is_empty() {
+            return Err("No multiway ranges set. Call setMultiwayRanges first.".to_string());
+        }
+
+        equity::multiway::calculate_multiway_equity(
+            &self.evaluator,
+            &self.cached_multiway_ranges,
+            board,
+            dead_cards.as_deref().unwrap_or_default(),
+            trials_per_combo
+        )
+    }
+
+    /// Short Deck (6+) equity for each hand in the Short Deck hero range vs the Short Deck villain range
+    /// Enumerates runouts from the 36 card deck for 3 or 4 card boards
+    /// Optional dead_cards never come and block combos holding them
+    /// IMPORTANT: Call setShortDeckHeroRange and setShortDeckVsRange before using this method
+    #[wasm_bindgen(js_name = shortDeckEquityVsRange)]
+    pub fn short_deck_equity_vs_range(
+        &self,
+        board: &[u8],
+        dead_cards: Option<Vec<u8>>,
+    ) -> Result<Vec<EquityResult>, String> {
+        let hero_range = self.cached_short_deck_hero_range.as_ref()
+            .ok_or("No Short Deck hero range set. Call setShortDeckHeroRange first.")?;
+        let vs_range = self.cached_short_deck_vs_range.as_ref()
+            .ok_or("No Short Deck villain range set. Call setShortDeckVsRange first.")?;
 
-        Ok(equity::holdem::calculate_leaf_equity(
-            &self.hand_ranks_data,
+        equity::short_deck::calculate_short_deck_equity_vs_range(
             hero_range,
             vs_range,
-            board
-        ))
+            board,
+            dead_cards.as_deref().unwrap_or_default()
+        )
     }
 
     /// Calculate Omaha equity for a single hand vs a range
     /// Returns equity for each possible runout
+    /// Optional dead_cards never come and block villain hands holding them
     /// IMPORTANT: Call setOmahaRange before using this method
     #[wasm_bindgen(js_name = omahaEquityVsRange)]
     pub fn omaha_equity_vs_range(
         &self,
         hero_hand: &[u8],
         board: &[u8],
+        dead_cards: Option<Vec<u8>>,
     ) -> Result<Vec<RunoutEquities>, String> {
         let vs_range = self.cached_omaha_range.as_ref()
             .ok_or("No Omaha range set. Call setOmahaRange first.")?;
 
         equity::omaha::calculate_omaha_equity_vs_range(
-            &self.hand_ranks_data,
+            &self.evaluator,
             hero_hand,
             vs_range,
-            board
+            board,
+            dead_cards.as_deref().unwrap_or_default()
         )
     }
 
     /// Calculate Omaha leaf equity (5-card board only, no enumeration)
     /// hero_hand must be 4, 5, or 6 cards (matching the range)
     /// board must be exactly 5 cards
+    /// Optional dead_cards block villain hands holding them
     /// IMPORTANT: Call setOmahaRange before using this method
     #[wasm_bindgen(js_name = omahaLeafEquityVsRange)]
     pub fn omaha_leaf_equity_vs_range(
         &self,
         hero_hand: &[u8],
         board: &[u8],
+        dead_cards: Option<Vec<u8>>,
     ) -> Result<RunoutEquities, String> {
         let vs_range = self.cached_omaha_range.as_ref()
             .ok_or("No Omaha range set. Call setOmahaRange first.")?;
 
-        if ![4, 5, 6].contains(&hero_hand.len()) {
-            return Err(format!("Hero hand must be 4, 5, or 6 cards, got {}", hero_hand.len()));
+        let board_cards: [u8; 5] = board.try_into()
+            .map_err(|_| "Board must be exactly 5 cards".to_string())?;
+
+        equity::omaha::calculate_omaha_leaf_equity(
+            &self.evaluator,
+            hero_hand,
+            vs_range,
+            &board_cards,
+            dead_cards.as_deref().unwrap_or_default()
+        )
+    }
+
+    /// Rank of a 5-7 card Hold'em hand, decode it with HandRankInfo.fromRank
+    #[wasm_bindgen(js_name = evalHoldem)]
+    pub fn eval_holdem(&self, cards: &[u8]) -> Result<i32, String> {
+        if cards.len() < 5 || cards.len() > 7 {
+            return Err(format!("Hand must have 5-7 cards, got {}", cards.len()));
         }
-        if hero_hand.len() != vs_range.hand_size() {
-            return Err(format!(
-                "Hero hand size ({}) must match range hand size ({})",
-                hero_hand.len(),
-                vs_range.hand_size()
-            ));
+        validate_cards(cards)?;
+
+        Ok(self.evaluator.eval(cards))
+    }
+
+    /// Rank of an Omaha hand (4, 5, or 6 cards) on a 5 card board
+    #[wasm_bindgen(js_name = evalOmaha)]
+    pub fn eval_omaha(&self, hand: &[u8], board: &[u8]) -> Result<i32, String> {
+        if ![4, 5, 6].contains(&hand.len()) {
+            return Err(format!("Omaha hand must be 4, 5, or 6 cards, got {}", hand.len()));
+        }
+        if board.len() != 5 {
+            return Err("Board must be exactly 5 cards".to_string());
+        }
+        let board_mask = validate_cards(board)?;
+        if validate_cards(hand)? & board_mask != 0 {
+            return Err("Hand and board share a card".to_string());
+        }
+
+        let board_cards = [board[0], board[1], board[2], board[3], board[4]];
+        Ok(equity::omaha::eval_omaha_hand(&self.evaluator, hand, &board_cards))
+    }
+
+    /// Rank many 2 card Hold'em hands on one 3-5 card board in a single call
+    /// hands is flattened, 2 cards per hand. Returns one rank per hand
+    #[wasm_bindgen(js_name = evalHoldemBatch)]
+    pub fn eval_holdem_batch(&self, board: &[u8], hands: &[u8]) -> Result<Vec<i32>, String> {
+        if board.len() < 3 || board.len() > 5 {
+            return Err("Board must have 3, 4, or 5 cards".to_string());
+        }
+        if !hands.len().is_multiple_of(2) {
+            return Err("Hands must contain 2 cards each".to_string());
+        }
+        let board_mask = validate_cards(board)?;
+
+        let board_eval = self.evaluator.board_eval(board);
+        hands
+            .chunks_exact(2)
+            .map(|hand| {
+                if validate_cards(hand)? & board_mask != 0 {
+                    return Err(format!("Hand {} shares a card with the board", hand_to_string(hand)));
+                }
+                Ok(board_eval(hand))
+            })
+            .collect()
+    }
+
+    /// Rank many Omaha hands on one 5 card board in a single call
+    /// hands is flattened, hand_size (4, 5, or 6) cards per hand. Returns one rank per hand
+    #[wasm_bindgen(js_name = evalOmahaBatch)]
+    pub fn eval_omaha_batch(
+        &self,
+        board: &[u8],
+        hands: &[u8],
+        hand_size: usize,
+    ) -> Result<Vec<i32>, String> {
+        if ![4, 5, 6].contains(&hand_size) {
+            return Err(format!("Omaha hand must be 4, 5, or 6 cards, got {hand_size}"));
+        }
+        if !hands.len().is_multiple_of(hand_size) {
+            return Err(format!("Hands must contain {hand_size} cards each"));
         }
         if board.len() != 5 {
             return Err("Board must be exactly 5 cards".to_string());
         }
+        let board_mask = validate_cards(board)?;
 
         let board_cards = [board[0], board[1], board[2], board[3], board[4]];
+        hands
+            .chunks_exact(hand_size)
+            .map(|hand| {
+                if validate_cards(hand)? & board_mask != 0 {
+                    return Err("Hand shares a card with the board".to_string());
+                }
+                Ok(equity::omaha::eval_omaha_hand(&self.evaluator, hand, &board_cards))
+            })
+            .collect()
+    }
+
+    /// Best five cards and rank for a Hold'em hand on a 3-5 card board
+    #[wasm_bindgen(js_name = bestHoldemHand)]
+    pub fn best_holdem_hand(
+        &self,
+        hand: &[u8],
+        board: &[u8],
+    ) -> Result<BestHand, String> {
+        let hand: &[u8; 2] = hand.try_into().map_err(|_| "Hand must contain exactly 2 cards".to_string())?;
+        evaluation::best_holdem_hand(&self.evaluator, hand, board)
+    }
+
+    /// Best five cards (2 hole cards then 3 board cards) and rank for an Omaha hand
+    /// hand must be 4, 5, or 6 cards, board must be exactly 5 cards
+    #[wasm_bindgen(js_name = bestOmahaHand)]
+    pub fn best_omaha_hand(
+        &self,
+        hand: &[u8],
+        board: &[u8],
+    ) -> Result<BestHand, String> {
+        let board_cards: &[u8; 5] = board.try_into().map_err(|_| "Board must be exactly 5 cards".to_string())?;
+        equity::omaha::best_omaha_hand(&self.evaluator, hand, board_cards)
+    }
+
+    /// Calculate Omaha Hi/Lo (8 or better) equity for a single hand vs a range
+    /// Returns scoop, high only, low only, quartered and pot share weights for each possible runout
+    /// Optional dead_cards never come and block villain hands holding them
+    /// IMPORTANT: Call setOmahaRange before using this method
+    #[wasm_bindgen(js_name = omahaHiLoEquityVsRange)]
+    pub fn omaha_hilo_equity_vs_range(
+        &self,
+        hero_hand: &[u8],
+        board: &[u8],
+        dead_cards: Option<Vec<u8>>,
+    ) -> Result<Vec<RunoutHiLoEquities>, String> {
+        let vs_range = self.cached_omaha_range.as_ref()
+            .ok_or("No Omaha range set. Call setOmahaRange first.")?;
 
-        Ok(equity::omaha::calculate_omaha_leaf_equity(
-            &self.hand_ranks_data,
+        equity::omaha_hilo::calculate_omaha_hilo_equity_vs_range(
+            &self.evaluator,
             hero_hand,
             vs_range,
-            &board_cards
-        ))
+            board,
+            dead_cards.as_deref().unwrap_or_default()
+        )
+    }
+
+    /// Calculate Omaha Hi/Lo leaf equity (5-card board only, no enumeration)
+    /// IMPORTANT: Call setOmahaRange before using this method
+    #[wasm_bindgen(js_name = omahaHiLoLeafEquityVsRange)]
+    pub fn omaha_hilo_leaf_equity_vs_range(
+        &self,
+        hero_hand: &[u8],
+        board: &[u8],
+        dead_cards: Option<Vec<u8>>,
+    ) -> Result<RunoutHiLoEquities, String> {
+        if board.len() != 5 {
+            return Err("Board must be exactly 5 cards".to_string());
+        }
+
+        self.omaha_hilo_equity_vs_range(hero_hand, board, dead_cards)
+            .map(|mut runouts| runouts.remove(0))
     }
 
     /// Calculate Omaha equity using Monte Carlo simulation on the flop
     /// hero_hand must be 4, 5, or 6 cards (matching the range)
     /// flop must be exactly 3 cards
     /// num_runouts controls accuracy vs speed tradeoff
+    /// Optional dead_cards never come and block villain hands holding them
     /// IMPORTANT: Call setOmahaRange before using this method
     #[wasm_bindgen(js_name = omahaMonteCarloFlop)]
     pub fn omaha_monte_carlo_flop(
@@ -163,6 +457,7 @@ impl EquityCalculator {
         hero_hand: &[u8],
         flop: &[u8],
         num_runouts: usize,
+        dead_cards: Option<Vec<u8>>,
     ) -> Result<Vec<RunoutEquities>, String> {
         let vs_range = self.cached_omaha_range.as_ref()
             .ok_or("No Omaha range set. Call setOmahaRange first.")?;
@@ -180,19 +475,47 @@ impl EquityCalculator {
         if flop.len() != 3 {
             return Err("Flop must be exactly 3 cards".to_string());
         }
+        let dead_cards = dead_cards.unwrap_or_default();
+        equity::omaha::hero_dead_card_mask(hero_hand, &dead_cards, flop)?;
 
         let flop_cards = [flop[0], flop[1], flop[2]];
 
         Ok(equity::omaha::calculate_omaha_equity_monte_carlo_flop(
-            &self.hand_ranks_data,
+            &self.evaluator,
             hero_hand,
             vs_range,
             &flop_cards,
-            num_runouts
+            num_runouts,
+            &dead_cards
         ))
     }
 }
 
+// Non-WASM impl block for internal Rust use
+impl EquityCalculator {
+    pub fn with_evaluator(evaluator: impl Into<CalculatorEvaluator>) -> Self {
+        EquityCalculator {
+            evaluator: evaluator.into(),
+            cached_hero_range: None,
+            cached_vs_range: None,
+            cached_omaha_range: None,
+            cached_short_deck_hero_range: None,
+            cached_short_deck_vs_range: None,
+            cached_multiway_ranges: Vec::new(),
+        }
+    }
+
+    /// Validated constructor, see `validate_hand_ranks`
+    pub fn try_new(data: Vec<u8>) -> Result<Self, RankTableError> {
+        validate_hand_ranks(&data)?;
+        Ok(Self::new(data))
+    }
+
+    pub fn evaluator(&self) -> &CalculatorEvaluator {
+        &self.evaluator
+    }
+}
+
 // --- WASM Bindings for Types ---
 // The types are defined in their respective modules, we just add WASM bindings here
 
@@ -233,4 +556,17 @@ impl EquityResult {
     }
 }
 
+#[wasm_bindgen]
+impl BestHand {
+    #[wasm_bindgen(getter)]
+    pub fn cards(&self) -> Vec<u8> {
+        self.cards.to_vec()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn rank(&self) -> i32 {
+        self.rank
+    }
+}
+
 // HoldemRange WASM bindings are in range/holdem.rs
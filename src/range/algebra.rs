@@ -0,0 +1,211 @@
+// Combining ranges by weight
+//
+// Weights are treated as frequencies: union takes the larger weight, intersection the smaller,
+// and subtraction removes b's weight from a's without going below 0. Omaha hands are matched by
+// their cards regardless of order, and hands that end up with weight <= 0 are dropped
+
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::range::{HoldemRange, OmahaRange};
+
+fn check_factor(name: &str, value: f32) -> Result<(), String> {
+    if value.is_finite() && value >= 0.0 {
+        Ok(())
+    } else {
+        Err(format!("{name} must be a finite number >= 0, got {value}"))
+    }
+}
+
+fn check_clamp(min: f32, max: f32) -> Result<(), String> {
+    // f32::clamp panics on NaN bounds or min > max
+    if min.is_nan() || max.is_nan() || min > max {
+        return Err(format!("Clamp min ({min}) must not be above max ({max})"));
+    }
+    Ok(())
+}
+
+fn check_mix(t: f32) -> Result<(), String> {
+    if (0.0..=1.0).contains(&t) {
+        Ok(())
+    } else {
+        Err(format!("Mix amount must be between 0 and 1, got {t}"))
+    }
+}
+
+#[wasm_bindgen]
+impl HoldemRange {
+    /// Larger weight of either range for each combo
+    #[wasm_bindgen]
+    pub fn union(&self, other: &HoldemRange) -> HoldemRange {
+        self.combine(other, f32::max)
+    }
+
+    /// Smaller weight of both ranges for each combo
+    #[wasm_bindgen]
+    pub fn intersect(&self, other: &HoldemRange) -> HoldemRange {
+        self.combine(other, f32::min)
+    }
+
+    /// This range's weight minus other's, at least 0
+    #[wasm_bindgen]
+    pub fn subtract(&self, other: &HoldemRange) -> HoldemRange {
+        self.combine(other, |a, b| (a - b).max(0.0))
+    }
+
+    /// Every weight multiplied by factor
+    #[wasm_bindgen]
+    pub fn scale(&self, factor: f32) -> Result<HoldemRange, String> {
+        check_factor("Scale factor", factor)?;
+        Ok(self.map_weights(|weight| weight * factor))
+    }
+
+    /// Scaled so the weights sum to total_combos
+    #[wasm_bindgen]
+    pub fn normalize(&self, total_combos: f32) -> Result<HoldemRange, String> {
+        check_factor("Total combos", total_combos)?;
+        let total = self.total_weight();
+        if total <= 0.0 {
+            return Err("Can't normalize an empty range".to_string());
+        }
+        Ok(self.map_weights(|weight| weight * total_combos / total))
+    }
+
+    /// Every weight limited to [min, max]
+    #[wasm_bindgen]
+    pub fn clamp(&self, min: f32, max: f32) -> Result<HoldemRange, String> {
+        check_clamp(min, max)?;
+        Ok(self.map_weights(|weight| weight.clamp(min, max)))
+    }
+
+    /// (1 - t) * this + t * other
+    #[wasm_bindgen]
+    pub fn mix(&self, other: &HoldemRange, t: f32) -> Result<HoldemRange, String> {
+        check_mix(t)?;
+        Ok(self.combine(other, |a, b| (1.0 - t) * a + t * b))
+    }
+
+    /// Sum of all weights, the number of combos in the range
+    #[wasm_bindgen(js_name = totalWeight)]
+    pub fn total_weight(&self) -> f32 {
+        self.range.iter().filter(|&&weight| weight > 0.0).sum()
+    }
+}
+
+// Non-WASM impl block for internal Rust use
+impl HoldemRange {
+    fn combine(&self, other: &HoldemRange, f: impl Fn(f32, f32) -> f32) -> HoldemRange {
+        HoldemRange {
+            range: self.range.iter().zip(&other.range).map(|(&a, &b)| f(a, b)).collect(),
+        }
+    }
+
+    fn map_weights(&self, f: impl Fn(f32) -> f32) -> HoldemRange {
+        HoldemRange {
+            range: self.range.iter().map(|&weight| f(weight)).collect(),
+        }
+    }
+}
+
+fn hand_mask(hand: &[u8]) -> u64 {
+    hand.iter().fold(0u64, |mask, &card| mask | (1u64 << card))
+}
+
+#[wasm_bindgen]
+impl OmahaRange {
+    /// Larger weight of either range for each hand
+    #[wasm_bindgen]
+    pub fn union(&self, other: &OmahaRange) -> Result<OmahaRange, String> {
+        self.combine(other, f32::max)
+    }
+
+    /// Hands in both ranges at the smaller weight
+    #[wasm_bindgen]
+    pub fn intersect(&self, other: &OmahaRange) -> Result<OmahaRange, String> {
+        self.combine(other, f32::min)
+    }
+
+    /// This range's weight minus other's, hands left at 0 are removed
+    #[wasm_bindgen]
+    pub fn subtract(&self, other: &OmahaRange) -> Result<OmahaRange, String> {
+        self.combine(other, |a, b| a - b)
+    }
+
+    /// Every weight multiplied by factor
+    #[wasm_bindgen]
+    pub fn scale(&self, factor: f32) -> Result<OmahaRange, String> {
+        check_factor("Scale factor", factor)?;
+        self.map_weights(|weight| weight * factor)
+    }
+
+    /// Scaled so the weights sum to total_combos
+    #[wasm_bindgen]
+    pub fn normalize(&self, total_combos: f32) -> Result<OmahaRange, String> {
+        check_factor("Total combos", total_combos)?;
+        let total = self.total_weight();
+        if total <= 0.0 {
+            return Err("Can't normalize an empty range".to_string());
+        }
+        self.map_weights(|weight| weight * total_combos / total)
+    }
+
+    /// Every weight limited to [min, max]
+    #[wasm_bindgen]
+    pub fn clamp(&self, min: f32, max: f32) -> Result<OmahaRange, String> {
+        check_clamp(min, max)?;
+        self.map_weights(|weight| weight.clamp(min, max))
+    }
+
+    /// (1 - t) * this + t * other, hands missing from a range count as weight 0
+    #[wasm_bindgen]
+    pub fn mix(&self, other: &OmahaRange, t: f32) -> Result<OmahaRange, String> {
+        check_mix(t)?;
+        self.combine(other, |a, b| (1.0 - t) * a + t * b)
+    }
+
+    /// Sum of all weights
+    #[wasm_bindgen(js_name = totalWeight)]
+    pub fn total_weight(&self) -> f32 {
+        self.iter().map(|(_, weight)| weight).filter(|&weight| weight > 0.0).sum()
+    }
+}
+
+// Non-WASM impl block for internal Rust use
+impl OmahaRange {
+    /// Applies f to (this weight, other weight) for every hand in either range,
+    /// in this range's order followed by hands only in other
+    fn combine(&self, other: &OmahaRange, f: impl Fn(f32, f32) -> f32) -> Result<OmahaRange, String> {
+        if self.get_hand_size() != other.get_hand_size() {
+            return Err(format!(
+                "Range hand sizes must match ({} and {})",
+                self.get_hand_size(),
+                other.get_hand_size()
+            ));
+        }
+
+        let mut hands: Vec<(&[u8], f32, f32)> = Vec::new();
+        let mut positions: HashMap<u64, usize> = HashMap::new();
+        for (range_idx, range) in [self, other].into_iter().enumerate() {
+            for (hand, weight) in range.iter() {
+                let pos = *positions.entry(hand_mask(hand)).or_insert_with(|| {
+                    hands.push((hand, 0.0, 0.0));
+                    hands.len() - 1
+                });
+                if range_idx == 0 {
+                    hands[pos].1 = weight;
+                } else {
+                    hands[pos].2 = weight;
+                }
+            }
+        }
+
+        let weights = hands.into_iter().map(|(hand, a, b)| (hand, f(a, b)));
+        OmahaRange::from_hands(self.get_hand_size(), weights.filter(|&(_, weight)| weight > 0.0))
+    }
+
+    fn map_weights(&self, f: impl Fn(f32) -> f32) -> Result<OmahaRange, String> {
+        let weights = self.iter().map(|(hand, weight)| (hand, f(weight)));
+        OmahaRange::from_hands(self.get_hand_size(), weights.filter(|&(_, weight)| weight > 0.0))
+    }
+}
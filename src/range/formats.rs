@@ -0,0 +1,194 @@
+// Range text formats of commercial solvers and range tools
+//
+// PioSOLVER: "AA,KK:0.5,AKs,AhKh:0.25", every class listed on its own with ":weight" suffixes
+// GTO+: "AA,[50]KK,QQ[/50],AKs", weights are percentages applying to the bracketed group
+// Flopzilla: standard notation ("AA-TT,AKs,A5s-A2s") without weights, so only 0/1 weights fit
+
+use wasm_bindgen::prelude::*;
+
+use crate::range::notation::{class_weights, combo_string, notation_items, parse_hands, with_weight, HandClass};
+use crate::range::{HoldemRange, RangeParseError};
+
+/// Text format for importing and exporting ranges
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RangeFormat {
+    Pio = 0,
+    GtoPlus = 1,
+    Flopzilla = 2,
+}
+
+fn percent_to_weight(percent: f64) -> f32 {
+    (percent / 100.0) as f32
+}
+
+/// Shortest percentage that reads back as exactly `weight`
+fn weight_to_percent(weight: f32) -> String {
+    let percent = weight as f64 * 100.0;
+    (0..=10)
+        .map(|decimals| format!("{percent:.decimals$}"))
+        .find(|text| text.parse().map(percent_to_weight) == Ok(weight))
+        .unwrap_or_else(|| percent.to_string())
+}
+
+/// Each class at its most common weight followed by the combos that differ from it
+fn class_items(range: &HoldemRange) -> Vec<(String, f32)> {
+    let mut items = Vec::new();
+    for class in HandClass::all() {
+        let (base, overrides) = class_weights(range, class, true);
+        if base > 0.0 {
+            items.push((class.name(), base));
+        }
+        items.extend(overrides.into_iter().map(|(idx, weight)| (combo_string(idx), weight)));
+    }
+    items
+}
+
+fn parse_percent(text: &str) -> Result<f64, String> {
+    match text.trim().parse::<f64>() {
+        Ok(percent) if percent.is_finite() && percent >= 0.0 => Ok(percent),
+        _ => Err(format!("Invalid weight '{}'", text.trim())),
+    }
+}
+
+fn parse_gto_plus(text: &str) -> Result<HoldemRange, RangeParseError> {
+    let mut range = HoldemRange::new();
+    // percentages of the open groups, innermost last
+    let mut groups: Vec<f64> = Vec::new();
+
+    let mut offset = 0;
+    for item in text.split(',') {
+        let item_offset = offset;
+        offset += item.len() + 1;
+
+        let trimmed = item.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let position = item_offset + (item.len() - item.trim_start().len());
+        let error = |message: String| RangeParseError { position, token: trimmed.to_string(), message };
+
+        let mut hands = trimmed;
+        if let Some(rest) = hands.strip_prefix('[') {
+            let (percent, rest) = rest.split_once(']').ok_or_else(|| error("Unclosed '['".to_string()))?;
+            groups.push(parse_percent(percent).map_err(error)?);
+            hands = rest;
+        }
+
+        let closes = match hands.rfind("[/") {
+            Some(idx) if hands.ends_with(']') => {
+                let percent = parse_percent(&hands[idx + 2..hands.len() - 1]).map_err(error)?;
+                hands = &hands[..idx];
+                Some(percent)
+            }
+            _ => None,
+        };
+
+        let weight = groups.last().copied().map_or(1.0, percent_to_weight);
+        for idx in parse_hands(hands.trim()).map_err(error)? {
+            range.set(idx, weight);
+        }
+
+        if let Some(percent) = closes {
+            match groups.pop() {
+                None => return Err(error("Closing a weight group that wasn't opened".to_string())),
+                Some(open) if open != percent => {
+                    return Err(error(format!("[/{percent}] doesn't close the [{open}] group")));
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    if !groups.is_empty() {
+        return Err(RangeParseError {
+            position: text.len(),
+            token: String::new(),
+            message: "Unclosed weight group".to_string(),
+        });
+    }
+    Ok(range)
+}
+
+#[wasm_bindgen]
+impl HoldemRange {
+    /// Parse a range exported from PioSOLVER, GTO+ or Flopzilla
+    #[wasm_bindgen(js_name = fromFormat)]
+    pub fn from_format_wasm(text: &str, format: RangeFormat) -> Result<HoldemRange, String> {
+        Self::from_format(text, format).map_err(|e| e.to_string())
+    }
+
+    /// Write the range for PioSOLVER, GTO+ or Flopzilla
+    /// Flopzilla fails for weights other than 0 or 1
+    #[wasm_bindgen(js_name = toFormat)]
+    pub fn to_format(&self, format: RangeFormat) -> Result<String, String> {
+        match format {
+            RangeFormat::Pio => Ok(self.to_pio()),
+            RangeFormat::GtoPlus => Ok(self.to_gto_plus()),
+            RangeFormat::Flopzilla => self.to_flopzilla(),
+        }
+    }
+}
+
+// Non-WASM impl block for internal Rust use
+impl HoldemRange {
+    pub fn from_format(text: &str, format: RangeFormat) -> Result<HoldemRange, RangeParseError> {
+        match format {
+            // both are covered by the standard notation
+            RangeFormat::Pio | RangeFormat::Flopzilla => text.parse(),
+            RangeFormat::GtoPlus => parse_gto_plus(text),
+        }
+    }
+
+    /// "AA,KK:0.5,AKs,AhKh:0.25"
+    pub fn to_pio(&self) -> String {
+        class_items(self)
+            .into_iter()
+            .map(|(item, weight)| with_weight(item, weight))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// "AA,[50]KK,QQ[/50],AKs", classes before combos so combos still override their class
+    pub fn to_gto_plus(&self) -> String {
+        // class names are 2 or 3 characters, specific combos 4
+        let (classes, combos): (Vec<_>, Vec<_>) = class_items(self)
+            .into_iter()
+            .partition(|(item, _)| item.len() <= 3);
+
+        let mut groups: Vec<String> = Vec::new();
+        for items in [classes, combos] {
+            // group by weight in order of first appearance
+            let mut by_weight: Vec<(f32, Vec<String>)> = Vec::new();
+            for (item, weight) in items {
+                match by_weight.iter_mut().find(|(w, _)| *w == weight) {
+                    Some((_, group)) => group.push(item),
+                    None => by_weight.push((weight, vec![item])),
+                }
+            }
+
+            for (weight, group) in by_weight {
+                let group = group.join(",");
+                if weight == 1.0 {
+                    groups.push(group);
+                } else {
+                    let percent = weight_to_percent(weight);
+                    groups.push(format!("[{percent}]{group}[/{percent}]"));
+                }
+            }
+        }
+        groups.join(",")
+    }
+
+    /// Standard notation, fails unless every weight is 0 or 1
+    pub fn to_flopzilla(&self) -> Result<String, String> {
+        if let Some(idx) = (0..1326).find(|&idx| ![0.0, 1.0].contains(&self.get_weight(idx).max(0.0))) {
+            return Err(format!(
+                "Flopzilla ranges can't hold weights other than 0 or 1, {} has {}",
+                combo_string(idx),
+                self.get_weight(idx)
+            ));
+        }
+        Ok(notation_items(self, false).join(","))
+    }
+}
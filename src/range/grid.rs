@@ -0,0 +1,151 @@
+// The 13x13 hand class grid used by range charts
+//
+// Class indices are row * 13 + col with row and col 0 = ace .. 12 = deuce. The diagonal holds
+// pairs, cells above it (row < col) suited hands and cells below it offsuit hands, so AKs is 1
+// and AKo is 13
+
+use wasm_bindgen::prelude::*;
+
+use crate::range::HoldemRange;
+use crate::EquityResult;
+
+/// Number of cells in the grid
+pub const NUM_HAND_CLASSES: usize = 169;
+
+/// Grid index of the class a 1326 hand index belongs to
+#[wasm_bindgen(js_name = handClassIndex)]
+pub fn hand_class_index(hand_idx: usize) -> usize {
+    let [c1, c2] = HoldemRange::from_hand_idx(hand_idx);
+    // from_hand_idx gives the lower card first, so c2 is the high card
+    let high = 12 - (c2 / 4) as usize;
+    let low = 12 - (c1 / 4) as usize;
+    if c1 % 4 == c2 % 4 {
+        high * 13 + low
+    } else {
+        low * 13 + high
+    }
+}
+
+/// Name of a grid cell, e.g. "AA", "AKs", "AKo"
+#[wasm_bindgen(js_name = handClassName)]
+pub fn hand_class_name(class_idx: usize) -> Result<String, String> {
+    if class_idx >= NUM_HAND_CLASSES {
+        return Err(format!("Hand class index {class_idx} must be below {NUM_HAND_CLASSES}"));
+    }
+    let ranks = b"AKQJT98765432";
+    let (row, col) = (class_idx / 13, class_idx % 13);
+    let high = ranks[row.min(col)] as char;
+    let low = ranks[row.max(col)] as char;
+    Ok(match row.cmp(&col) {
+        std::cmp::Ordering::Equal => format!("{high}{low}"),
+        std::cmp::Ordering::Less => format!("{high}{low}s"),
+        std::cmp::Ordering::Greater => format!("{high}{low}o"),
+    })
+}
+
+/// Hand indices of every combo in a grid cell (6 for pairs, 4 suited, 12 offsuit)
+pub fn hand_class_combos(class_idx: usize) -> Vec<usize> {
+    (0..1326).filter(|&idx| hand_class_index(idx) == class_idx).collect()
+}
+
+#[wasm_bindgen]
+impl HoldemRange {
+    /// Average weight of each of the 169 grid cells
+    #[wasm_bindgen(js_name = toGrid)]
+    pub fn to_grid(&self) -> Vec<f32> {
+        let mut sums = [0.0f32; NUM_HAND_CLASSES];
+        let mut counts = [0u32; NUM_HAND_CLASSES];
+        for (idx, &weight) in self.range.iter().enumerate() {
+            let class_idx = hand_class_index(idx);
+            sums[class_idx] += weight;
+            counts[class_idx] += 1;
+        }
+        sums.iter().zip(counts).map(|(&sum, count)| sum / count as f32).collect()
+    }
+
+    /// Range with every combo of a cell at that cell's weight
+    #[wasm_bindgen(js_name = fromGrid)]
+    pub fn from_grid(grid: &[f32]) -> Result<HoldemRange, String> {
+        if grid.len() != NUM_HAND_CLASSES {
+            return Err(format!("Grid must have {NUM_HAND_CLASSES} weights, got {}", grid.len()));
+        }
+        let mut range = HoldemRange::new();
+        for (idx, weight) in range.range.iter_mut().enumerate() {
+            *weight = grid[hand_class_index(idx)];
+        }
+        Ok(range)
+    }
+}
+
+/// Equity of one grid cell, averaged over its combos by hero's weight
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ClassEquity {
+    pub(crate) class_idx: usize,
+    pub(crate) combos: u32,
+    pub(crate) weight: f32,
+    pub(crate) equity: f32,
+}
+
+#[wasm_bindgen]
+impl ClassEquity {
+    #[wasm_bindgen(getter, js_name = classIdx)]
+    pub fn class_idx(&self) -> usize {
+        self.class_idx
+    }
+
+    /// Combos of the cell that had a result
+    #[wasm_bindgen(getter)]
+    pub fn combos(&self) -> u32 {
+        self.combos
+    }
+
+    /// Sum of hero's weights over those combos
+    #[wasm_bindgen(getter)]
+    pub fn weight(&self) -> f32 {
+        self.weight
+    }
+
+    /// Weighted average of (win + tie / 2) / (win + tie + lose), 0 without results
+    #[wasm_bindgen(getter)]
+    pub fn equity(&self) -> f32 {
+        self.equity
+    }
+}
+
+/// Aggregate per combo results (e.g. from equity_vs_range) into the 169 grid cells
+#[wasm_bindgen(js_name = aggregateByClass)]
+pub fn aggregate_by_class_wasm(results: Vec<EquityResult>, hero_range: &HoldemRange) -> Vec<ClassEquity> {
+    aggregate_by_class(&results, hero_range)
+}
+
+/// Aggregate per combo results into the 169 grid cells, weighting each combo by hero's range
+/// Combos that are entirely blocked (no villain weight) are skipped
+pub fn aggregate_by_class(results: &[EquityResult], hero_range: &HoldemRange) -> Vec<ClassEquity> {
+    let mut classes: Vec<ClassEquity> = (0..NUM_HAND_CLASSES)
+        .map(|class_idx| ClassEquity { class_idx, ..Default::default() })
+        .collect();
+    let mut equity_sums = [0.0f32; NUM_HAND_CLASSES];
+
+    for result in results {
+        let equity = result.equity;
+        let total = equity.win + equity.tie + equity.lose;
+        let weight = hero_range.get_weight(result.hand_idx);
+        if total <= 0.0 || weight <= 0.0 {
+            continue;
+        }
+
+        let class_idx = hand_class_index(result.hand_idx);
+        let class = &mut classes[class_idx];
+        class.combos += 1;
+        class.weight += weight;
+        equity_sums[class_idx] += weight * (equity.win + equity.tie / 2.0) / total;
+    }
+
+    for (class, sum) in classes.iter_mut().zip(equity_sums) {
+        if class.weight > 0.0 {
+            class.equity = sum / class.weight;
+        }
+    }
+    classes
+}
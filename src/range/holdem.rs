@@ -1,4 +1,5 @@
 use crate::evaluation::IDX2HAND;
+use crate::range::RangeParseError;
 use wasm_bindgen::prelude::*;
 
 /// Represents a range of Texas Hold'em hands.
@@ -23,6 +24,18 @@ impl HoldemRange {
         Self::default()
     }
 
+    /// Parse standard range notation like "AA-TT, AKs, A5s-A2s, KQo:0.5, T9s-65s, T9, AhKh"
+    #[wasm_bindgen(js_name = fromString)]
+    pub fn from_string_wasm(notation: &str) -> Result<HoldemRange, String> {
+        notation.parse().map_err(|e: RangeParseError| e.to_string())
+    }
+
+    /// Compact range notation, fromString gives back the same range
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_string_wasm(&self) -> String {
+        self.to_string()
+    }
+
     #[wasm_bindgen]
     pub fn get_range(&self) -> Vec<f32> {
         self.range.clone()
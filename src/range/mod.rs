@@ -1,5 +1,17 @@
+mod algebra;
+mod formats;
+mod grid;
 mod holdem;
+mod notation;
 mod omaha;
+mod omaha_notation;
+mod ranking;
+mod short_deck;
 
+pub use formats::*;
+pub use grid::*;
 pub use holdem::*;
+pub use notation::*;
 pub use omaha::*;
+pub use ranking::*;
+pub use short_deck::*;
@@ -0,0 +1,378 @@
+// Standard Hold'em range notation, e.g. "AA-TT, AKs, A5s-A2s, KQo:0.5, T9s-65s, T9, AhKh"
+//
+// Items are comma separated, each a hand class ("AKs", "AKo", "AK" for both, "TT"), a span
+// ("TT+", "A9s+", "AA-TT", "A5s-A2s"), or a specific combo ("AhKh"), with an optional
+// ":weight" suffix (default 1). "+" raises the kicker up to one below the top card, so "KQs+" is
+// only KQs, and connectors need a span like "KQs-54s". Later items overwrite earlier ones
+//
+// Formatting goes the other way: each class is written at its most common weight, with runs of
+// equal weight merged into spans, then any combos that differ from their class as specific combos
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::evaluation::{card_to_string, RANKS, SUITS};
+use crate::range::HoldemRange;
+
+/// Why a range string couldn't be parsed, with the byte offset of the offending item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeParseError {
+    pub position: usize,
+    pub token: String,
+    pub message: String,
+}
+
+impl fmt::Display for RangeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {} ('{}')", self.message, self.position, self.token)
+    }
+}
+
+impl std::error::Error for RangeParseError {}
+
+/// Pair, suited, offsuit, or both suited and offsuit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Suitedness {
+    Pair,
+    Suited,
+    Offsuit,
+    Any,
+}
+
+/// A hand class like AKs, ranks 0 = deuce .. 12 = ace with high >= low
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct HandClass {
+    pub(crate) high: u8,
+    pub(crate) low: u8,
+    pub(crate) suitedness: Suitedness,
+}
+
+impl HandClass {
+    /// Class name like "AA", "AKs" or "AKo", "AK" when both suited and offsuit
+    pub(crate) fn name(&self) -> String {
+        let (high, low) = (rank_char(self.high), rank_char(self.low));
+        match self.suitedness {
+            Suitedness::Pair | Suitedness::Any => format!("{high}{low}"),
+            Suitedness::Suited => format!("{high}{low}s"),
+            Suitedness::Offsuit => format!("{high}{low}o"),
+        }
+    }
+
+    /// The 169 pair, suited and offsuit classes, pairs first then by high card and kicker
+    pub(crate) fn all() -> Vec<HandClass> {
+        let mut classes: Vec<HandClass> = (0..13u8)
+            .rev()
+            .map(|rank| HandClass { high: rank, low: rank, suitedness: Suitedness::Pair })
+            .collect();
+        for high in (1..13u8).rev() {
+            for suitedness in [Suitedness::Suited, Suitedness::Offsuit] {
+                classes.extend((0..high).rev().map(|low| HandClass { high, low, suitedness }));
+            }
+        }
+        classes
+    }
+
+    /// Every combo of the class as hand indices
+    pub(crate) fn hand_indices(&self) -> Vec<usize> {
+        let mut indices = Vec::new();
+        for s1 in 0..4u8 {
+            for s2 in 0..4u8 {
+                let matches = match self.suitedness {
+                    Suitedness::Pair => s1 < s2,
+                    Suitedness::Suited => s1 == s2,
+                    Suitedness::Offsuit => s1 != s2,
+                    Suitedness::Any => true,
+                };
+                if matches {
+                    let hand = [self.high * 4 + s1, self.low * 4 + s2];
+                    indices.push(HoldemRange::get_hand_idx(hand));
+                }
+            }
+        }
+        indices
+    }
+}
+
+fn parse_rank(c: char) -> Option<u8> {
+    RANKS.find(c.to_ascii_uppercase()).map(|idx| idx as u8)
+}
+
+fn parse_suit(c: char) -> Option<u8> {
+    SUITS.find(c.to_ascii_lowercase()).map(|idx| idx as u8)
+}
+
+/// Parses "AK", "AKs", "AKo" or "TT"
+fn parse_class(text: &str) -> Result<HandClass, String> {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() != 2 && chars.len() != 3 {
+        return Err("Expected a hand like AKs, AKo, AK or TT".to_string());
+    }
+
+    let r1 = parse_rank(chars[0]).ok_or_else(|| format!("Invalid rank '{}'", chars[0]))?;
+    let r2 = parse_rank(chars[1]).ok_or_else(|| format!("Invalid rank '{}'", chars[1]))?;
+    let (high, low) = if r1 >= r2 { (r1, r2) } else { (r2, r1) };
+
+    let suitedness = match (chars.get(2), high == low) {
+        (None, true) => Suitedness::Pair,
+        (Some(_), true) => return Err("Pairs can't be suited or offsuit".to_string()),
+        (None, false) => Suitedness::Any,
+        (Some('s'), false) | (Some('S'), false) => Suitedness::Suited,
+        (Some('o'), false) | (Some('O'), false) => Suitedness::Offsuit,
+        (Some(c), false) => return Err(format!("Expected 's' or 'o', got '{c}'")),
+    };
+
+    Ok(HandClass { high, low, suitedness })
+}
+
+/// Parses a specific combo like "AhKh"
+fn parse_combo(text: &str) -> Option<Result<usize, String>> {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() != 4 {
+        return None;
+    }
+    let card = |rank: char, suit: char| Some(parse_rank(rank)? * 4 + parse_suit(suit)?);
+    let c1 = card(chars[0], chars[1])?;
+    let c2 = card(chars[2], chars[3])?;
+    if c1 == c2 {
+        return Some(Err("Combo uses the same card twice".to_string()));
+    }
+    Some(Ok(HoldemRange::get_hand_idx([c1, c2])))
+}
+
+/// Classes from `from` up to `to` (inclusive) along the only direction that fits both
+fn span(from: HandClass, to: HandClass) -> Result<Vec<HandClass>, String> {
+    if from.suitedness != to.suitedness {
+        return Err("Both ends of a span must have the same suitedness".to_string());
+    }
+
+    if from.suitedness == Suitedness::Pair {
+        let (lo, hi) = (from.high.min(to.high), from.high.max(to.high));
+        return Ok((lo..=hi)
+            .map(|rank| HandClass { high: rank, low: rank, suitedness: Suitedness::Pair })
+            .collect());
+    }
+
+    if from.high == to.high {
+        // kicker span, e.g. A5s-A2s
+        let (lo, hi) = (from.low.min(to.low), from.low.max(to.low));
+        return Ok((lo..=hi)
+            .map(|low| HandClass { high: from.high, low, suitedness: from.suitedness })
+            .collect());
+    }
+
+    if from.high - from.low == to.high - to.low {
+        // same gap, e.g. T9s-65s
+        let gap = from.high - from.low;
+        let (lo, hi) = (from.high.min(to.high), from.high.max(to.high));
+        return Ok((lo..=hi)
+            .map(|high| HandClass { high, low: high - gap, suitedness: from.suitedness })
+            .collect());
+    }
+
+    Err("Span ends must share the top card or the gap between cards".to_string())
+}
+
+/// "TT+" or "A9s+"
+fn plus(class: HandClass) -> Vec<HandClass> {
+    let HandClass { high, low, suitedness } = class;
+    if suitedness == Suitedness::Pair {
+        (high..13).map(|rank| HandClass { high: rank, low: rank, suitedness }).collect()
+    } else {
+        (low..high).map(|low| HandClass { high, low, suitedness }).collect()
+    }
+}
+
+/// Hand indices of one item without its weight
+pub(crate) fn parse_hands(text: &str) -> Result<Vec<usize>, String> {
+    if let Some(combo) = parse_combo(text) {
+        return combo.map(|idx| vec![idx]);
+    }
+
+    let classes = if let Some((from, to)) = text.split_once('-') {
+        span(parse_class(from.trim())?, parse_class(to.trim())?)?
+    } else if let Some(base) = text.strip_suffix('+') {
+        plus(parse_class(base.trim())?)
+    } else {
+        vec![parse_class(text)?]
+    };
+
+    Ok(classes.iter().flat_map(|class| class.hand_indices()).collect())
+}
+
+fn parse_weight(text: &str) -> Result<f32, String> {
+    match text.trim().parse::<f32>() {
+        Ok(weight) if weight.is_finite() && weight >= 0.0 => Ok(weight),
+        _ => Err(format!("Invalid weight '{}'", text.trim())),
+    }
+}
+
+impl FromStr for HoldemRange {
+    type Err = RangeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut range = HoldemRange::new();
+
+        let mut offset = 0;
+        for item in s.split(',') {
+            let item_offset = offset;
+            offset += item.len() + 1;
+
+            let trimmed = item.trim_start();
+            let position = item_offset + (item.len() - trimmed.len());
+            let trimmed = trimmed.trim_end();
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            let error = |message: String| RangeParseError {
+                position,
+                token: trimmed.to_string(),
+                message,
+            };
+
+            let (hands, weight) = match trimmed.split_once(':') {
+                Some((hands, weight)) => (hands.trim_end(), parse_weight(weight).map_err(error)?),
+                None => (trimmed, 1.0),
+            };
+
+            for idx in parse_hands(hands).map_err(error)? {
+                range.set(idx, weight);
+            }
+        }
+
+        Ok(range)
+    }
+}
+
+fn rank_char(rank: u8) -> char {
+    RANKS.as_bytes()[rank as usize] as char
+}
+
+pub(crate) fn with_weight(item: String, weight: f32) -> String {
+    if weight == 1.0 {
+        item
+    } else {
+        format!("{item}:{weight}")
+    }
+}
+
+/// Most common weight among the combos of a class (ties go to the lower weight),
+/// plus the combos that differ from it. Weights <= 0 count as 0
+/// Without `overrides` the class only gets a weight when all its combos share it
+pub(crate) fn class_weights(range: &HoldemRange, class: HandClass, overrides: bool) -> (f32, Vec<(usize, f32)>) {
+    let combos: Vec<(usize, f32)> = class
+        .hand_indices()
+        .into_iter()
+        .map(|idx| (idx, range.get_weight(idx).max(0.0)))
+        .collect();
+
+    let mut base = 0.0;
+    let mut base_count = 0;
+    for &(_, weight) in &combos {
+        let count = combos.iter().filter(|&&(_, w)| w == weight).count();
+        if count > base_count || (count == base_count && weight < base) {
+            base = weight;
+            base_count = count;
+        }
+    }
+    if !overrides && base_count != combos.len() {
+        base = 0.0;
+    }
+
+    let overrides = combos.into_iter().filter(|&(_, w)| w != base).collect();
+    (base, overrides)
+}
+
+/// Runs of equal non-zero weights as (first, last, weight), positions ascending
+fn runs(weights: &[f32]) -> Vec<(u8, u8, f32)> {
+    let mut runs: Vec<(u8, u8, f32)> = Vec::new();
+    for (pos, &weight) in weights.iter().enumerate() {
+        if weight == 0.0 {
+            continue;
+        }
+        match runs.last_mut() {
+            Some(run) if run.1 + 1 == pos as u8 && run.2 == weight => run.1 = pos as u8,
+            _ => runs.push((pos as u8, pos as u8, weight)),
+        }
+    }
+    runs
+}
+
+fn pair_item(first: u8, last: u8) -> String {
+    let (lo, hi) = (rank_char(first), rank_char(last));
+    if first == last {
+        format!("{hi}{hi}")
+    } else if last == 12 {
+        format!("{lo}{lo}+")
+    } else {
+        format!("{hi}{hi}-{lo}{lo}")
+    }
+}
+
+fn kicker_item(high: u8, first: u8, last: u8, suffix: &str) -> String {
+    let h = rank_char(high);
+    let (lo, hi) = (rank_char(first), rank_char(last));
+    if first == last {
+        format!("{h}{hi}{suffix}")
+    } else if last + 1 == high {
+        format!("{h}{lo}{suffix}+")
+    } else {
+        format!("{h}{hi}{suffix}-{h}{lo}{suffix}")
+    }
+}
+
+/// Specific combo like "AhKh", highest card first
+pub(crate) fn combo_string(idx: usize) -> String {
+    let [c1, c2] = HoldemRange::from_hand_idx(idx);
+    format!("{}{}", card_to_string(c2), card_to_string(c1))
+}
+
+/// Items of the compact notation, see the Display impl. With `overrides` a class is written at
+/// its most common weight and differing combos follow it, otherwise only uniform classes are
+/// written as classes and every combo of the other classes is listed
+pub(crate) fn notation_items(range: &HoldemRange, overrides: bool) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut combos = Vec::new();
+
+    let mut class_base = |high: u8, low: u8, suitedness: Suitedness| {
+        let (base, differing) = class_weights(range, HandClass { high, low, suitedness }, overrides);
+        combos.extend(differing);
+        base
+    };
+
+    let pairs: Vec<f32> = (0..13).map(|rank| class_base(rank, rank, Suitedness::Pair)).collect();
+    for &(first, last, weight) in runs(&pairs).iter().rev() {
+        items.push(with_weight(pair_item(first, last), weight));
+    }
+
+    for high in (1..13u8).rev() {
+        let suited: Vec<f32> = (0..high).map(|low| class_base(high, low, Suitedness::Suited)).collect();
+        let offsuit: Vec<f32> = (0..high).map(|low| class_base(high, low, Suitedness::Offsuit)).collect();
+        let suited_runs = runs(&suited);
+        let offsuit_runs = runs(&offsuit);
+
+        // identical suited and offsuit runs are written once without a suffix
+        for run @ &(first, last, weight) in suited_runs.iter().rev() {
+            let suffix = if offsuit_runs.contains(run) { "" } else { "s" };
+            items.push(with_weight(kicker_item(high, first, last, suffix), weight));
+        }
+        for run @ &(first, last, weight) in offsuit_runs.iter().rev() {
+            if !suited_runs.contains(run) {
+                items.push(with_weight(kicker_item(high, first, last, "o"), weight));
+            }
+        }
+    }
+
+    for (idx, weight) in combos {
+        items.push(with_weight(combo_string(idx), weight));
+    }
+
+    items
+}
+
+impl fmt::Display for HoldemRange {
+    /// Compact notation that parses back to the same weights
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", notation_items(self, true).join(", "))
+    }
+}
@@ -1,7 +1,12 @@
 use wasm_bindgen::prelude::*;
 
+use crate::evaluation::validate_cards;
+
 /// Omaha range representation - simple array of hands with weights
 /// Supports PLO4 (4 cards), PLO5 (5 cards), and PLO6 (6 cards)
+///
+/// Hands are kept canonical: each hand's cards are stored highest first and hands are sorted
+/// from highest to lowest, so every hand appears once whatever order its cards were given in
 #[wasm_bindgen]
 #[derive(Debug, Clone, PartialEq)]
 pub struct OmahaRange {
@@ -12,34 +17,136 @@ pub struct OmahaRange {
     hand_size: usize, // 4, 5, or 6
 }
 
+fn check_weight(weight: f32) -> Result<(), String> {
+    if weight.is_finite() && weight >= 0.0 {
+        Ok(())
+    } else {
+        Err(format!("Weight must be a finite number >= 0, got {weight}"))
+    }
+}
+
 #[wasm_bindgen]
 impl OmahaRange {
     /// Create a new empty Omaha range with specified hand size (4, 5, or 6)
     #[wasm_bindgen(constructor)]
-    pub fn new(hand_size: usize) -> Self {
+    pub fn try_new(hand_size: usize) -> Result<OmahaRange, String> {
         if ![4, 5, 6].contains(&hand_size) {
-            panic!("Hand size must be 4, 5, or 6");
-        }
-        Self {
-            hands: Vec::new(),
-            weights: Vec::new(),
-            hand_size,
+            return Err(format!("Hand size must be 4, 5, or 6, got {hand_size}"));
         }
+        Ok(Self::empty(hand_size))
+    }
+
+    /// Parse notation like "AA**, KKQQ$ds, RRxx!AAxx, [A-T][A-T]**@50" into hand_size card hands
+    #[wasm_bindgen(js_name = fromString)]
+    pub fn from_string_wasm(notation: &str, hand_size: usize) -> Result<OmahaRange, String> {
+        Self::parse(notation, hand_size).map_err(|e| e.to_string())
     }
 
-    /// Add a hand to the range with a weight
-    /// hand must match the range's hand_size (4, 5, or 6 cards)
+    /// Add a hand to the range with a weight, replacing the weight if the hand is already in it
+    /// hand must match the range's hand_size (4, 5, or 6 cards), in any order
+    /// Each add keeps the range sorted, use from_hands to build large ranges
     #[wasm_bindgen(js_name = addHand)]
-    pub fn add_hand(&mut self, hand: &[u8], weight: f32) {
-        if hand.len() != self.hand_size {
-            panic!("Hand must have exactly {} cards", self.hand_size);
-        }
-        let mut hand_array = [0u8; 6];
-        for (i, &card) in hand.iter().enumerate() {
-            hand_array[i] = card;
+    pub fn try_add_hand(&mut self, hand: &[u8], weight: f32) -> Result<(), String> {
+        check_weight(weight)?;
+        let key = self.canonical(hand)?;
+        match self.position(&key) {
+            Ok(pos) => self.weights[pos] = weight,
+            Err(pos) => {
+                self.hands.insert(pos, key);
+                self.weights.insert(pos, weight);
+            }
         }
-        self.hands.push(hand_array);
-        self.weights.push(weight);
+        Ok(())
+    }
+
+    /// Whether the range has the hand, in any card order
+    #[wasm_bindgen]
+    pub fn contains(&self, hand: &[u8]) -> bool {
+        self.weight_of(hand).is_some()
+    }
+
+    /// Weight of a hand, undefined if it isn't in the range
+    #[wasm_bindgen(js_name = weightOf)]
+    pub fn weight_of(&self, hand: &[u8]) -> Option<f32> {
+        let key = self.canonical(hand).ok()?;
+        self.position(&key).ok().map(|pos| self.weights[pos])
+    }
+
+    /// Change the weight of a hand already in the range
+    #[wasm_bindgen(js_name = setWeight)]
+    pub fn set_weight(&mut self, hand: &[u8], weight: f32) -> Result<(), String> {
+        check_weight(weight)?;
+        let key = self.canonical(hand)?;
+        let pos = self
+            .position(&key)
+            .map_err(|_| format!("Hand {hand:?} isn't in the range"))?;
+        self.weights[pos] = weight;
+        Ok(())
+    }
+
+    /// Remove a hand, returning whether it was in the range
+    #[wasm_bindgen(js_name = removeHand)]
+    pub fn remove_hand(&mut self, hand: &[u8]) -> bool {
+        let Some(pos) = self.canonical(hand).ok().and_then(|key| self.position(&key).ok()) else {
+            return false;
+        };
+        self.hands.remove(pos);
+        self.weights.remove(pos);
+        true
+    }
+
+    /// Remove every hand holding one of `cards` (e.g. the board), returning how many were removed
+    /// Fails without changing the range on invalid or repeated cards
+    #[wasm_bindgen(js_name = removeBlocked)]
+    pub fn remove_blocked(&mut self, cards: &[u8]) -> Result<usize, String> {
+        let dead = validate_cards(cards)?;
+        let before = self.len();
+        self.retain(|hand, _| hand.iter().all(|&card| dead & (1u64 << card) == 0));
+        Ok(before - self.len())
+    }
+
+    /// Keep only hands for which predicate(hand: Uint8Array, weight: number) is truthy
+    /// The range is left unchanged if predicate throws
+    #[wasm_bindgen(js_name = retain)]
+    pub fn retain_wasm(&mut self, predicate: &js_sys::Function) -> Result<(), String> {
+        let keep = self
+            .iter()
+            .map(|(hand, weight)| {
+                let hand = js_sys::Uint8Array::from(hand);
+                predicate
+                    .call2(&JsValue::NULL, &hand, &JsValue::from(weight))
+                    .map(|keep| keep.is_truthy())
+                    .map_err(|e| e.as_string().unwrap_or_else(|| format!("{e:?}")))
+            })
+            .collect::<Result<Vec<bool>, String>>()?;
+
+        let mut keep = keep.into_iter();
+        self.retain(|_, _| keep.next().expect("one flag per hand"));
+        Ok(())
+    }
+
+    /// Every hand's cards back to back, hand_size bytes per hand in range order
+    #[wasm_bindgen(js_name = hands)]
+    pub fn hands_wasm(&self) -> Vec<u8> {
+        self.iter().flat_map(|(hand, _)| hand.iter().copied()).collect()
+    }
+
+    /// Weights in range order, matching hands()
+    #[wasm_bindgen(js_name = weights)]
+    pub fn weights_wasm(&self) -> Vec<f32> {
+        self.weights.clone()
+    }
+
+    /// Cards of the hand at idx, highest first
+    #[wasm_bindgen(js_name = handAt)]
+    pub fn hand_at(&self, idx: usize) -> Option<Vec<u8>> {
+        self.get_hand(idx).map(|hand| hand.to_vec())
+    }
+
+    /// Weight of the hand at idx
+    #[wasm_bindgen(js_name = weightAt)]
+    pub fn weight_at(&self, idx: usize) -> Option<f32> {
+        self.get_weight(idx)
     }
 
     /// Get the number of hands in the range
@@ -63,6 +170,62 @@ impl OmahaRange {
 
 // Internal methods (not exposed to WASM)
 impl OmahaRange {
+    /// Create a new empty Omaha range with specified hand size (4, 5, or 6)
+    ///
+    /// # Panics
+    ///
+    /// Panics if hand_size isn't 4, 5, or 6
+    #[deprecated(note = "use try_new, which returns the error instead of panicking")]
+    pub fn new(hand_size: usize) -> Self {
+        Self::try_new(hand_size).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Add a hand to the range with a weight, replacing the weight if the hand is already in it
+    ///
+    /// # Panics
+    ///
+    /// Panics if the hand doesn't have hand_size distinct valid cards or the weight is negative
+    /// or not finite
+    #[deprecated(note = "use try_add_hand, which returns the error instead of panicking")]
+    pub fn add_hand(&mut self, hand: &[u8], weight: f32) {
+        self.try_add_hand(hand, weight).unwrap_or_else(|e| panic!("{e}"));
+    }
+
+    /// Empty range for a hand size already known to be 4, 5 or 6
+    pub(crate) fn empty(hand_size: usize) -> Self {
+        Self {
+            hands: Vec::new(),
+            weights: Vec::new(),
+            hand_size,
+        }
+    }
+
+    /// Build a range from many hands at once, much faster than repeated add_hand
+    /// Later copies of a hand replace earlier ones like add_hand
+    pub fn from_hands<H: AsRef<[u8]>>(
+        hand_size: usize,
+        hands: impl IntoIterator<Item = (H, f32)>,
+    ) -> Result<OmahaRange, String> {
+        let mut range = Self::try_new(hand_size)?;
+        let mut entries = Vec::new();
+        for (hand, weight) in hands {
+            check_weight(weight)?;
+            entries.push((range.canonical(hand.as_ref())?, weight));
+        }
+
+        // stable, so equal hands stay in input order and the last one wins
+        entries.sort_by_key(|&(key, _)| std::cmp::Reverse(key));
+        for (key, weight) in entries {
+            if range.hands.last() == Some(&key) {
+                *range.weights.last_mut().expect("same length as hands") = weight;
+            } else {
+                range.hands.push(key);
+                range.weights.push(weight);
+            }
+        }
+        Ok(range)
+    }
+
     /// Iterator over (hand slice, weight) pairs
     /// Returns only the valid portion of each hand based on hand_size
     pub fn iter(&self) -> impl Iterator<Item = (&[u8], f32)> + '_ {
@@ -70,6 +233,21 @@ impl OmahaRange {
         self.hands.iter().map(move |h| &h[..hand_size]).zip(self.weights.iter().copied())
     }
 
+    /// Keep only hands for which f(hand, weight) is true
+    pub fn retain(&mut self, mut f: impl FnMut(&[u8], f32) -> bool) {
+        let hand_size = self.hand_size;
+        let mut kept = 0;
+        for idx in 0..self.hands.len() {
+            if f(&self.hands[idx][..hand_size], self.weights[idx]) {
+                self.hands.swap(kept, idx);
+                self.weights.swap(kept, idx);
+                kept += 1;
+            }
+        }
+        self.hands.truncate(kept);
+        self.weights.truncate(kept);
+    }
+
     /// Get a specific hand by index (returns slice of valid cards)
     pub fn get_hand(&self, idx: usize) -> Option<&[u8]> {
         self.hands.get(idx).map(|h| &h[..self.hand_size])
@@ -84,10 +262,27 @@ impl OmahaRange {
     pub fn get_hand_size(&self) -> usize {
         self.hand_size
     }
+
+    /// Storage form of a hand: checked, cards highest first, padded with 0
+    fn canonical(&self, hand: &[u8]) -> Result<[u8; 6], String> {
+        if hand.len() != self.hand_size {
+            return Err(format!("Hand must have exactly {} cards, got {}", self.hand_size, hand.len()));
+        }
+        validate_cards(hand)?;
+        let mut key = [0u8; 6];
+        key[..hand.len()].copy_from_slice(hand);
+        key[..hand.len()].sort_unstable_by(|a, b| b.cmp(a));
+        Ok(key)
+    }
+
+    /// Index of a canonical hand, or where it would be inserted
+    fn position(&self, key: &[u8; 6]) -> Result<usize, usize> {
+        self.hands.binary_search_by(|hand| key.cmp(hand))
+    }
 }
 
 impl Default for OmahaRange {
     fn default() -> Self {
-        Self::new(4) // Default to PLO4
+        Self::empty(4) // Default to PLO4
     }
-}
\ No newline at end of file
+}
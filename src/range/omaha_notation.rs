@@ -0,0 +1,405 @@
+// ProPokerTools style Omaha range notation, e.g. "AA**, KKQQ$ds, RRxx!AAxx, [A-T][A-T]**$ds@50"
+//
+// Each card of a pattern is a rank part followed by an optional suit part:
+//   "*"                any rank
+//   "A", "T", "2"      a rank
+//   "[AKQ]", "[A-T]"   any of a set of ranks
+//   "R", "O", "M", "N" rank variables, repeats of a letter share a rank and different letters differ
+//   "c", "d", "h", "s" a suit ("As", "*h")
+//   "w", "x", "y", "z" suit variables, like rank variables ("AxKx" is suited AK)
+// A suit on its own is any card of that suit, and a lone "x" is any card. An "x" right after a
+// rank is its suit variable unless another "x" or "*" follows it, so "AAxx" and "RRxx" are a pair
+// plus any two cards while "AxKx" is suited AK
+// Patterns shorter than the hand size are padded with "*", so "AA" is the same as "AA**"
+//
+// "$ds" (double suited), "$ss" (single suited), "$r" (rainbow) and "$np" (no pair) filter the
+// hands a pattern matches, "!pattern" removes hands matching another pattern, and "@50" (percent)
+// or ":0.5" sets the weight. Items are comma separated and later items overwrite earlier ones.
+// Every concrete hand is kept once no matter how many times or card orders it matches, and suit
+// isomorphic hands are all kept as separate hands so each has its real frequency. A pattern or
+// range that would expand to more than MAX_PATTERN_HANDS hands is rejected
+
+use std::collections::HashMap;
+
+use crate::evaluation::{RANKS, SUITS};
+use crate::range::{OmahaRange, RangeParseError};
+
+/// Most hands a pattern can match or a range can hold, every 5 card hand
+/// Any PLO4 or PLO5 pattern fits, "******" for PLO6 (~20M hands) doesn't
+const MAX_PATTERN_HANDS: usize = 2_598_960;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum RankSpec {
+    Set(u16),
+    Var(usize),
+    Any,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SuitSpec {
+    Fixed(u8),
+    Var(usize),
+    Any,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct CardSpec {
+    rank: RankSpec,
+    suit: SuitSpec,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Macro {
+    DoubleSuited,
+    SingleSuited,
+    Rainbow,
+    NoPair,
+}
+
+impl Macro {
+    fn matches(self, hand: &[u8]) -> bool {
+        let mut suit_counts = [0u8; 4];
+        let mut rank_counts = [0u8; 13];
+        for &card in hand {
+            suit_counts[(card % 4) as usize] += 1;
+            rank_counts[(card / 4) as usize] += 1;
+        }
+        let suited_suits = suit_counts.iter().filter(|&&count| count >= 2).count();
+        match self {
+            Macro::DoubleSuited => suited_suits >= 2,
+            Macro::SingleSuited => suited_suits == 1,
+            Macro::Rainbow => suited_suits == 0,
+            Macro::NoPair => rank_counts.iter().all(|&count| count <= 1),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Pattern {
+    cards: Vec<CardSpec>,
+    macros: Vec<Macro>,
+}
+
+// (position in the input, message)
+type ParseResult<T> = Result<T, (usize, String)>;
+
+fn rank_var(c: char) -> Option<usize> {
+    "ROMN".find(c)
+}
+
+fn suit_var(c: char) -> Option<usize> {
+    "wxyz".find(c)
+}
+
+fn parse_rank_set(chars: &[(usize, char)], start: usize) -> ParseResult<(u16, usize)> {
+    let mut mask = 0u16;
+    let mut i = start + 1;
+    loop {
+        let Some(&(pos, c)) = chars.get(i) else {
+            return Err((chars[start].0, "Unclosed '['".to_string()));
+        };
+        if c == ']' {
+            break;
+        }
+        let rank = RANKS.find(c).ok_or((pos, format!("Invalid rank '{c}' in rank set")))?;
+        if chars.get(i + 1).map(|&(_, c)| c) == Some('-') {
+            let &(end_pos, end) = chars.get(i + 2).ok_or((pos, "Unfinished rank span".to_string()))?;
+            let end = RANKS.find(end).ok_or((end_pos, format!("Invalid rank '{end}' in rank set")))?;
+            for r in rank.min(end)..=rank.max(end) {
+                mask |= 1 << r;
+            }
+            i += 3;
+        } else {
+            mask |= 1 << rank;
+            i += 1;
+        }
+    }
+    if mask == 0 {
+        return Err((chars[start].0, "Empty rank set".to_string()));
+    }
+    Ok((mask, i + 1))
+}
+
+/// One pattern with its macros, `offset` is the position of `text` in the whole input
+fn parse_pattern(text: &str, offset: usize, hand_size: usize) -> ParseResult<Pattern> {
+    let (cards_text, macros_text) = match text.find('$') {
+        Some(idx) => text.split_at(idx),
+        None => (text, ""),
+    };
+
+    let chars: Vec<(usize, char)> = cards_text.char_indices().map(|(i, c)| (offset + i, c)).collect();
+    let mut cards = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let (pos, c) = chars[i];
+        let rank = if c == '*' {
+            i += 1;
+            Some(RankSpec::Any)
+        } else if c == 'x' {
+            i += 1;
+            cards.push(CardSpec { rank: RankSpec::Any, suit: SuitSpec::Any });
+            continue;
+        } else if let Some(rank) = RANKS.find(c) {
+            i += 1;
+            Some(RankSpec::Set(1 << rank))
+        } else if let Some(var) = rank_var(c) {
+            i += 1;
+            Some(RankSpec::Var(var))
+        } else if c == '[' {
+            let (mask, next) = parse_rank_set(&chars, i)?;
+            i = next;
+            Some(RankSpec::Set(mask))
+        } else {
+            None
+        };
+
+        // "xx" or "x*" after a rank are lone cards rather than a suit variable and a card
+        let lone_x = |at: usize| chars.get(at).map(|&(_, c)| c) == Some('x')
+            && matches!(chars.get(at + 1), Some(&(_, 'x' | '*')));
+        let suit = match chars.get(i).map(|&(_, c)| c) {
+            Some(c) if SUITS.contains(c) => Some(SuitSpec::Fixed(SUITS.find(c).unwrap() as u8)),
+            Some(c) if rank.is_some() && suit_var(c).is_some() && !lone_x(i) => {
+                Some(SuitSpec::Var(suit_var(c).unwrap()))
+            }
+            _ => None,
+        };
+        if suit.is_some() {
+            i += 1;
+        }
+
+        if rank.is_none() && suit.is_none() {
+            return Err(match suit_var(c) {
+                Some(_) => (pos, format!("Suit variable '{c}' must follow a rank or '*'")),
+                None => (pos, format!("Invalid card '{c}'")),
+            });
+        }
+        cards.push(CardSpec {
+            rank: rank.unwrap_or(RankSpec::Any),
+            suit: suit.unwrap_or(SuitSpec::Any),
+        });
+    }
+
+    if cards.is_empty() {
+        return Err((offset, "Empty pattern".to_string()));
+    }
+    if cards.len() > hand_size {
+        return Err((offset, format!("Pattern has {} cards, hands have {hand_size}", cards.len())));
+    }
+    cards.resize(hand_size, CardSpec { rank: RankSpec::Any, suit: SuitSpec::Any });
+    // most constrained first, and identical specs next to each other
+    cards.sort();
+
+    let mut macros = Vec::new();
+    let macro_offset = offset + cards_text.len();
+    let mut pos = macro_offset;
+    for name in macros_text.split('$').skip(1) {
+        macros.push(match name {
+            "ds" => Macro::DoubleSuited,
+            "ss" => Macro::SingleSuited,
+            "r" => Macro::Rainbow,
+            "np" => Macro::NoPair,
+            _ => return Err((pos, format!("Unknown macro '${name}'"))),
+        });
+        pos += name.len() + 1;
+    }
+
+    Ok(Pattern { cards, macros })
+}
+
+/// Backtracking assignment of cards to specs, with variable bindings
+struct Search<'a> {
+    specs: &'a [CardSpec],
+    candidates: &'a [u8],
+    ranks: [Option<u8>; 4],
+    suits: [Option<u8>; 4],
+    cards: [u8; 6],
+}
+
+impl Search<'_> {
+    /// Calls `found` for every assignment until it returns true
+    fn run(&mut self, pos: usize, used: u64, found: &mut dyn FnMut(&[u8]) -> bool) -> bool {
+        if pos == self.specs.len() {
+            return found(&self.cards[..pos]);
+        }
+
+        let spec = self.specs[pos];
+        // identical specs take cards in increasing order so each set is only built once
+        let min_card = if pos > 0 && self.specs[pos - 1] == spec { self.cards[pos - 1] + 1 } else { 0 };
+
+        for &card in self.candidates {
+            if card < min_card || used & (1u64 << card) != 0 {
+                continue;
+            }
+            let (rank, suit) = (card / 4, card % 4);
+
+            let bind_rank = match spec.rank {
+                RankSpec::Any => None,
+                RankSpec::Set(mask) if mask & (1 << rank) != 0 => None,
+                RankSpec::Set(_) => continue,
+                RankSpec::Var(var) => match self.ranks[var] {
+                    Some(bound) if bound == rank => None,
+                    Some(_) => continue,
+                    None if self.ranks.contains(&Some(rank)) => continue,
+                    None => Some(var),
+                },
+            };
+            let bind_suit = match spec.suit {
+                SuitSpec::Any => None,
+                SuitSpec::Fixed(fixed) if fixed == suit => None,
+                SuitSpec::Fixed(_) => continue,
+                SuitSpec::Var(var) => match self.suits[var] {
+                    Some(bound) if bound == suit => None,
+                    Some(_) => continue,
+                    None if self.suits.contains(&Some(suit)) => continue,
+                    None => Some(var),
+                },
+            };
+
+            if let Some(var) = bind_rank {
+                self.ranks[var] = Some(rank);
+            }
+            if let Some(var) = bind_suit {
+                self.suits[var] = Some(suit);
+            }
+            self.cards[pos] = card;
+
+            let done = self.run(pos + 1, used | (1u64 << card), found);
+
+            if let Some(var) = bind_rank {
+                self.ranks[var] = None;
+            }
+            if let Some(var) = bind_suit {
+                self.suits[var] = None;
+            }
+            if done {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl Pattern {
+    fn search<'a>(&'a self, candidates: &'a [u8]) -> Search<'a> {
+        Search { specs: &self.cards, candidates, ranks: [None; 4], suits: [None; 4], cards: [0; 6] }
+    }
+
+    /// Whether the hand (cards ascending) matches
+    fn matches(&self, hand: &[u8]) -> bool {
+        self.macros.iter().all(|m| m.matches(hand)) && self.search(hand).run(0, 0, &mut |_| true)
+    }
+
+    /// Every hand matching the pattern, cards ascending, passed to `f`
+    /// Stops and returns false after MAX_PATTERN_HANDS hands before the macros are checked
+    fn expand(&self, f: &mut dyn FnMut(&[u8])) -> bool {
+        let deck: Vec<u8> = (0..52).collect();
+        let mut count = 0;
+        !self.search(&deck).run(0, 0, &mut |cards| {
+            count += 1;
+            if count > MAX_PATTERN_HANDS {
+                return true;
+            }
+            let mut hand = [0u8; 6];
+            hand[..cards.len()].copy_from_slice(cards);
+            let hand = &mut hand[..cards.len()];
+            hand.sort_unstable();
+            if self.macros.iter().all(|m| m.matches(hand)) {
+                f(hand);
+            }
+            false
+        })
+    }
+}
+
+fn parse_weight(text: &str, offset: usize) -> ParseResult<f32> {
+    let invalid = || (offset, format!("Invalid weight '{text}'"));
+    let weight = if let Some(percent) = text.strip_prefix('@') {
+        percent.trim_end_matches('%').trim().parse::<f32>().map_err(|_| invalid())? / 100.0
+    } else {
+        text[1..].trim().parse::<f32>().map_err(|_| invalid())?
+    };
+    if !weight.is_finite() || weight < 0.0 {
+        return Err(invalid());
+    }
+    Ok(weight)
+}
+
+/// Hands of one item with their weight
+fn parse_item(
+    item: &str,
+    offset: usize,
+    hand_size: usize,
+    weights: &mut HashMap<u64, ([u8; 6], f32)>,
+) -> ParseResult<()> {
+    let (patterns, weight) = match item.find(['@', ':']) {
+        Some(idx) => (&item[..idx], parse_weight(&item[idx..], offset + idx)?),
+        None => (item, 1.0),
+    };
+
+    let mut parts = Vec::new();
+    let mut part_offset = offset;
+    for part in patterns.split('!') {
+        let trimmed = part.trim();
+        let lead = part.len() - part.trim_start().len();
+        parts.push(parse_pattern(trimmed, part_offset + lead, hand_size)?);
+        part_offset += part.len() + 1;
+    }
+
+    let (include, exclude) = parts.split_first().expect("split always yields a part");
+    let too_many = || (offset, format!("Pattern matches more than {MAX_PATTERN_HANDS} hands"));
+    let expanded = include.expand(&mut |hand| {
+        if exclude.iter().any(|pattern| pattern.matches(hand)) {
+            return;
+        }
+        let mask = hand.iter().fold(0u64, |mask, &card| mask | (1u64 << card));
+        let mut cards = [0u8; 6];
+        // stored highest card first
+        for (slot, &card) in cards.iter_mut().zip(hand.iter().rev()) {
+            *slot = card;
+        }
+        weights.insert(mask, (cards, weight));
+    });
+    if !expanded {
+        return Err(too_many());
+    }
+    if weights.len() > MAX_PATTERN_HANDS {
+        return Err((offset, format!("Range has more than {MAX_PATTERN_HANDS} hands")));
+    }
+
+    Ok(())
+}
+
+impl OmahaRange {
+    /// Parse ProPokerTools style notation like "AA**, KKQQ$ds, RRxx!AAxx, [A-T][A-T]**@50"
+    /// into a range of `hand_size` card hands. Hands that end up with weight 0 are left out
+    pub fn parse(notation: &str, hand_size: usize) -> Result<OmahaRange, RangeParseError> {
+        if ![4, 5, 6].contains(&hand_size) {
+            return Err(RangeParseError {
+                position: 0,
+                token: hand_size.to_string(),
+                message: "Hand size must be 4, 5, or 6".to_string(),
+            });
+        }
+
+        let mut weights = HashMap::new();
+        let mut offset = 0;
+        for item in notation.split(',') {
+            let item_offset = offset;
+            offset += item.len() + 1;
+
+            let trimmed = item.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            let lead = item.len() - item.trim_start().len();
+
+            parse_item(trimmed, item_offset + lead, hand_size, &mut weights).map_err(
+                |(position, message)| RangeParseError { position, token: trimmed.to_string(), message },
+            )?;
+        }
+
+        let hands: Vec<_> = weights.into_values().filter(|&(_, weight)| weight > 0.0).collect();
+        Ok(OmahaRange::from_hands(hand_size, hands.iter().map(|(cards, weight)| (&cards[..hand_size], *weight)))
+            .expect("parsed hands are valid"))
+    }
+}
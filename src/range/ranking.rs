@@ -0,0 +1,386 @@
+// Preflop hand rankings and "top X%" ranges built from them
+//
+// Hands are grouped into suit isomorphic classes (the 169 Hold'em classes, ~16k PLO4 classes)
+// and each class is ranked by its average all-in equity. Hold'em equities are exact, summed from
+// the preflop matchup table. PLO4 equities are estimated by a fixed-seed Monte Carlo so the same
+// ordering always gives the same ranking. Rankings vs a random hand are computed once and cached.
+// A top X% range takes whole classes from the top, and gives the class that crosses the cutoff a
+// partial weight so the range holds exactly X% of all hands
+
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use wasm_bindgen::prelude::*;
+
+use crate::evaluation::combinations::{BOARD_COMBOS_3_FROM_5, HOLE_COMBOS_2_FROM_4};
+use crate::equity::preflop::calculate_preflop_equity_vs_range;
+use crate::evaluation::{permute_suit, score_cards, SUIT_PERMUTATIONS};
+use crate::range::{hand_class_index, HoldemRange, OmahaRange, NUM_HAND_CLASSES};
+use crate::Equity;
+
+/// What preflop hands are ranked against
+#[derive(Debug, Clone, PartialEq)]
+pub enum HandOrdering<R> {
+    /// Equity vs a uniformly random hand
+    VsRandom,
+    /// Equity vs a weighted range
+    VsRange(R),
+}
+
+// keep OmahaRange::top_percent's doc in sync with these
+const OMAHA_BOARDS: usize = 600;
+const OMAHA_VILLAINS_PER_BOARD: usize = 100;
+const RANKING_SEED: u64 = 0x5eed_7a61;
+// attempts at drawing a villain hand that doesn't collide with hero's before giving up on a trial
+const VILLAIN_ATTEMPTS: usize = 64;
+
+lazy_static! {
+    static ref HOLDEM_CLASSES: Vec<Vec<[u8; 2]>> = {
+        let mut classes = vec![Vec::new(); NUM_HAND_CLASSES];
+        for idx in 0..1326 {
+            classes[hand_class_index(idx)].push(HoldemRange::from_hand_idx(idx));
+        }
+        classes
+    };
+    static ref PLO4_CLASSES: Vec<Vec<[u8; 4]>> = plo4_classes();
+    static ref HOLDEM_VS_RANDOM: Vec<(usize, f32)> = holdem_ranking(None);
+    static ref PLO4_VS_RANDOM: Vec<(usize, f32)> = plo4_ranking(None);
+}
+
+/// Every PLO4 hand grouped by suit isomorphism
+fn plo4_classes() -> Vec<Vec<[u8; 4]>> {
+    let mut class_of: HashMap<[u8; 4], usize> = HashMap::new();
+    let mut classes: Vec<Vec<[u8; 4]>> = Vec::new();
+    for c1 in 0..52u8 {
+        for c2 in c1 + 1..52 {
+            for c3 in c2 + 1..52 {
+                for c4 in c3 + 1..52 {
+                    let hand = [c1, c2, c3, c4];
+                    let key = SUIT_PERMUTATIONS
+                        .iter()
+                        .map(|perm| {
+                            let mut cards = hand.map(|card| permute_suit(card, perm));
+                            cards.sort_unstable();
+                            cards
+                        })
+                        .min()
+                        .expect("24 permutations");
+                    let class_idx = *class_of.entry(key).or_insert_with(|| {
+                        classes.push(Vec::new());
+                        classes.len() - 1
+                    });
+                    classes[class_idx].push(hand);
+                }
+            }
+        }
+    }
+    classes
+}
+
+/// Draws hands from a range in proportion to their weights
+struct VillainSampler<'a> {
+    hands: Vec<&'a [u8]>,
+    cumulative: Vec<f32>,
+}
+
+impl<'a> VillainSampler<'a> {
+    fn new(hands: impl Iterator<Item = (&'a [u8], f32)>) -> Self {
+        let mut sampler = VillainSampler { hands: Vec::new(), cumulative: Vec::new() };
+        let mut total = 0.0;
+        for (hand, weight) in hands.filter(|&(_, weight)| weight > 0.0) {
+            total += weight;
+            sampler.hands.push(hand);
+            sampler.cumulative.push(total);
+        }
+        sampler
+    }
+
+    /// Weighted hand that avoids `dead`, None if the range has no weight or keeps colliding
+    fn sample(&self, rng: &mut StdRng, dead: u64) -> Option<&'a [u8]> {
+        let total = *self.cumulative.last()?;
+        for _ in 0..VILLAIN_ATTEMPTS {
+            let target = rng.random::<f32>() * total;
+            let idx = self.cumulative.partition_point(|&cum| cum <= target).min(self.hands.len() - 1);
+            let hand = self.hands[idx];
+            if hand.iter().all(|&card| dead & (1u64 << card) == 0) {
+                return Some(hand);
+            }
+        }
+        None
+    }
+}
+
+/// Fills `cards` with random cards not in `dead`, returning the new dead mask
+fn deal(rng: &mut StdRng, mut dead: u64, cards: &mut [u8]) -> u64 {
+    for slot in cards {
+        let card = loop {
+            let card = rng.random_range(0..52u8);
+            if dead & (1u64 << card) == 0 {
+                break card;
+            }
+        };
+        dead |= 1u64 << card;
+        *slot = card;
+    }
+    dead
+}
+
+fn mask(cards: &[u8]) -> u64 {
+    cards.iter().fold(0u64, |mask, &card| mask | (1u64 << card))
+}
+
+/// Sorts (class, equity) from best to worst, ties keep class order
+fn sort_ranking(mut ranking: Vec<(usize, f32)>) -> Vec<(usize, f32)> {
+    ranking.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranking
+}
+
+/// Grid classes by exact average equity vs a random hand (None) or a range
+fn holdem_ranking(vs_range: Option<&HoldemRange>) -> Vec<(usize, f32)> {
+    let mut any_two = HoldemRange::new();
+    for idx in 0..1326 {
+        any_two.set(idx, 1.0);
+    }
+
+    // pot share and villain weight of each class, summed over its combos and every board
+    let mut totals = vec![[0.0f64; 2]; NUM_HAND_CLASSES];
+    for result in calculate_preflop_equity_vs_range(&any_two, vs_range.unwrap_or(&any_two)) {
+        let Equity { win, tie, lose } = result.equity;
+        let total = &mut totals[hand_class_index(result.hand_idx)];
+        total[0] += win as f64 + tie as f64 / 2.0;
+        total[1] += win as f64 + tie as f64 + lose as f64;
+    }
+
+    let ranking = totals
+        .iter()
+        .enumerate()
+        .map(|(class_idx, &[share, total])| (class_idx, if total > 0.0 { (share / total) as f32 } else { 0.0 }))
+        .collect();
+    sort_ranking(ranking)
+}
+
+/// Best rank each 2 card hole pair makes with any 3 cards of one board, so an Omaha hand's
+/// rank is the best of 6 lookups instead of 60 evaluations
+struct BoardPairRanks {
+    board_mask: u64,
+    ranks: Vec<i32>,
+}
+
+impl BoardPairRanks {
+    fn new(board: &[u8; 5]) -> Self {
+        let board_mask = mask(board);
+        let mut ranks = vec![i32::MIN; 1326];
+        for &[b1, b2, b3] in BOARD_COMBOS_3_FROM_5.iter() {
+            for (idx, rank) in ranks.iter_mut().enumerate() {
+                let [h1, h2] = HoldemRange::from_hand_idx(idx);
+                if mask(&[h1, h2]) & board_mask == 0 {
+                    *rank = (*rank).max(score_cards(&[h1, h2, board[b1], board[b2], board[b3]]) as i32);
+                }
+            }
+        }
+        BoardPairRanks { board_mask, ranks }
+    }
+
+    /// Rank of a PLO4 hand that doesn't overlap the board
+    fn omaha_rank(&self, hand: &[u8; 4]) -> i32 {
+        HOLE_COMBOS_2_FROM_4
+            .iter()
+            .map(|&[h1, h2]| self.ranks[HoldemRange::get_hand_idx([hand[h1], hand[h2]])])
+            .max()
+            .expect("6 hole pairs")
+    }
+}
+
+/// PLO4 classes by average equity vs a random hand (None) or a 4 card range
+///
+/// Each sampled board is shared by every class and a pool of villain hands, with the board's
+/// pair ranks computed once
+fn plo4_ranking(vs_range: Option<&OmahaRange>) -> Vec<(usize, f32)> {
+    let sampler = vs_range.map(|range| VillainSampler::new(range.iter()));
+
+    let mut rng = StdRng::seed_from_u64(RANKING_SEED);
+    let mut half_points = vec![0u32; PLO4_CLASSES.len()];
+    let mut trials = vec![0u32; PLO4_CLASSES.len()];
+    let mut villain_masks: Vec<u64> = Vec::with_capacity(OMAHA_VILLAINS_PER_BOARD);
+    let mut villain_ranks: Vec<i32> = Vec::with_capacity(OMAHA_VILLAINS_PER_BOARD);
+
+    for _ in 0..OMAHA_BOARDS {
+        let mut board = [0u8; 5];
+        deal(&mut rng, 0, &mut board);
+        let pair_ranks = BoardPairRanks::new(&board);
+
+        villain_masks.clear();
+        villain_ranks.clear();
+        for _ in 0..OMAHA_VILLAINS_PER_BOARD {
+            let mut villain = [0u8; 4];
+            match &sampler {
+                Some(sampler) => match sampler.sample(&mut rng, pair_ranks.board_mask) {
+                    Some(hand) => villain.copy_from_slice(hand),
+                    None => continue,
+                },
+                None => {
+                    deal(&mut rng, pair_ranks.board_mask, &mut villain);
+                }
+            }
+            villain_masks.push(mask(&villain));
+            villain_ranks.push(pair_ranks.omaha_rank(&villain));
+        }
+
+        for (class_idx, members) in PLO4_CLASSES.iter().enumerate() {
+            // any member not blocked by the board, starting from a random one
+            let start = rng.random_range(0..members.len());
+            let Some(hero) = (0..members.len())
+                .map(|offset| &members[(start + offset) % members.len()])
+                .find(|hand| mask(&hand[..]) & pair_ranks.board_mask == 0)
+            else {
+                continue;
+            };
+            let hero_mask = mask(hero);
+            let hero_rank = pair_ranks.omaha_rank(hero);
+
+            let (mut live_villains, mut points) = (0u32, 0u32);
+            // branch free since outcomes are unpredictable, counting half points so ties are 1
+            for (&villain_mask, &villain_rank) in villain_masks.iter().zip(&villain_ranks) {
+                let live = (villain_mask & hero_mask == 0) as u32;
+                live_villains += live;
+                points += live * (2 * (hero_rank > villain_rank) as u32 + (hero_rank == villain_rank) as u32);
+            }
+            trials[class_idx] += live_villains;
+            half_points[class_idx] += points;
+        }
+    }
+
+    let ranking = half_points
+        .iter()
+        .zip(&trials)
+        .enumerate()
+        .map(|(class_idx, (&points, &trials))| {
+            (class_idx, if trials > 0 { points as f32 / (2 * trials) as f32 } else { 0.0 })
+        })
+        .collect();
+    sort_ranking(ranking)
+}
+
+fn check_percent(pct: f32) -> Result<(), String> {
+    if (0.0..=100.0).contains(&pct) {
+        Ok(())
+    } else {
+        Err(format!("Percentage must be between 0 and 100, got {pct}"))
+    }
+}
+
+fn check_vs_range(total_weight: f32) -> Result<(), String> {
+    if total_weight > 0.0 {
+        Ok(())
+    } else {
+        Err("Can't rank hands against an empty range".to_string())
+    }
+}
+
+/// Weight of each class in ranking order so the total is pct% of `total_hands`
+fn top_weights(
+    ranking: &[(usize, f32)],
+    class_size: impl Fn(usize) -> usize,
+    pct: f32,
+    total_hands: usize,
+) -> Vec<(usize, f32)> {
+    let mut remaining = pct as f64 / 100.0 * total_hands as f64;
+    let mut weights = Vec::new();
+    for &(class_idx, _) in ranking {
+        if remaining <= 0.0 {
+            break;
+        }
+        let size = class_size(class_idx) as f64;
+        weights.push((class_idx, (remaining / size).min(1.0) as f32));
+        remaining -= size;
+    }
+    weights
+}
+
+#[wasm_bindgen]
+impl HoldemRange {
+    /// The top pct% of hands by equity vs a random hand
+    #[wasm_bindgen(js_name = topPercent)]
+    pub fn top_percent_wasm(pct: f32) -> Result<HoldemRange, String> {
+        Self::top_percent(pct, &HandOrdering::VsRandom)
+    }
+
+    /// The top pct% of hands by equity vs vs_range
+    #[wasm_bindgen(js_name = topPercentVsRange)]
+    pub fn top_percent_vs_range_wasm(pct: f32, vs_range: &HoldemRange) -> Result<HoldemRange, String> {
+        Self::top_percent(pct, &HandOrdering::VsRange(vs_range.clone()))
+    }
+}
+
+// Non-WASM impl block for internal Rust use
+impl HoldemRange {
+    /// 13x13 grid classes (see hand_class_index) from best to worst with their equity
+    /// Every equity is 0 against a range without weight
+    pub fn hand_ranking(ordering: &HandOrdering<HoldemRange>) -> Vec<(usize, f32)> {
+        match ordering {
+            HandOrdering::VsRandom => HOLDEM_VS_RANDOM.clone(),
+            HandOrdering::VsRange(range) => holdem_ranking(Some(range)),
+        }
+    }
+
+    /// Range of the best pct% of the 1326 combos, e.g. top_percent(15.0, &HandOrdering::VsRandom)
+    pub fn top_percent(pct: f32, ordering: &HandOrdering<HoldemRange>) -> Result<HoldemRange, String> {
+        check_percent(pct)?;
+        if let HandOrdering::VsRange(range) = ordering {
+            check_vs_range(range.total_weight())?;
+        }
+        let ranking = Self::hand_ranking(ordering);
+        let weights = top_weights(&ranking, |class_idx| HOLDEM_CLASSES[class_idx].len(), pct, 1326);
+
+        let mut range = HoldemRange::new();
+        for (class_idx, weight) in weights {
+            for &combo in &HOLDEM_CLASSES[class_idx] {
+                range.set(HoldemRange::get_hand_idx(combo), weight);
+            }
+        }
+        Ok(range)
+    }
+}
+
+#[wasm_bindgen]
+impl OmahaRange {
+    /// The top pct% of PLO4 hands by equity vs a random hand, estimated by a fixed-seed Monte Carlo
+    #[wasm_bindgen(js_name = topPercent)]
+    pub fn top_percent_wasm(pct: f32) -> Result<OmahaRange, String> {
+        Self::top_percent(pct, &HandOrdering::VsRandom)
+    }
+
+    /// The top pct% of PLO4 hands by equity vs a PLO4 range
+    #[wasm_bindgen(js_name = topPercentVsRange)]
+    pub fn top_percent_vs_range_wasm(pct: f32, vs_range: &OmahaRange) -> Result<OmahaRange, String> {
+        Self::top_percent(pct, &HandOrdering::VsRange(vs_range.clone()))
+    }
+}
+
+// Non-WASM impl block for internal Rust use
+impl OmahaRange {
+    /// Range of the best pct% of the 270725 PLO4 hands, each hand's cards highest first
+    /// The ranking is approximate: each class's equity is a Monte Carlo estimate over 600 boards
+    /// with 100 villain hands each, seeded with 0x5eed_7a61 so every call ranks hands the same way
+    pub fn top_percent(pct: f32, ordering: &HandOrdering<OmahaRange>) -> Result<OmahaRange, String> {
+        check_percent(pct)?;
+        let ranking = match ordering {
+            HandOrdering::VsRandom => PLO4_VS_RANDOM.clone(),
+            HandOrdering::VsRange(range) => {
+                if range.get_hand_size() != 4 {
+                    return Err(format!("Range must be PLO4, got {} card hands", range.get_hand_size()));
+                }
+                check_vs_range(range.total_weight())?;
+                plo4_ranking(Some(range))
+            }
+        };
+        let weights = top_weights(&ranking, |class_idx| PLO4_CLASSES[class_idx].len(), pct, 270725);
+
+        let hands = weights
+            .into_iter()
+            .flat_map(|(class_idx, weight)| PLO4_CLASSES[class_idx].iter().map(move |hand| (hand, weight)));
+        OmahaRange::from_hands(4, hands)
+    }
+}
@@ -0,0 +1,92 @@
+use crate::evaluation::is_short_deck_card;
+use crate::range::HoldemRange;
+use wasm_bindgen::prelude::*;
+
+/// Range of Short Deck hands, only the 630 combos of six through ace can have weight
+/// Uses the same 1326 hand indices as HoldemRange
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ShortDeckRange {
+    pub(crate) range: HoldemRange,
+}
+
+#[wasm_bindgen]
+impl ShortDeckRange {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[wasm_bindgen]
+    pub fn get_range(&self) -> Vec<f32> {
+        self.range.get_range()
+    }
+
+    #[wasm_bindgen]
+    pub fn get_weight(&self, idx: usize) -> f32 {
+        self.range.get_weight(idx)
+    }
+
+    #[wasm_bindgen]
+    pub fn set(&mut self, idx: usize, weight: f32) -> Result<(), String> {
+        if !Self::is_valid_hand_idx(idx) {
+            return Err(format!("Hand index {idx} is not a Short Deck combo"));
+        }
+        self.range.set(idx, weight);
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn set_hand(&mut self, hand: &[u8], weight: f32) -> Result<(), String> {
+        if hand.len() != 2 {
+            return Err("Hand must contain exactly 2 cards".to_string());
+        }
+        if !is_short_deck_card(hand[0]) || !is_short_deck_card(hand[1]) || hand[0] == hand[1] {
+            return Err(format!("Invalid Short Deck hand: {hand:?}"));
+        }
+        self.range.set_hand(hand, weight)
+    }
+}
+
+// Non-WASM impl block for internal Rust use
+impl ShortDeckRange {
+    /// Whether both cards of the hand index are in the short deck
+    pub fn is_valid_hand_idx(idx: usize) -> bool {
+        if idx >= 1326 {
+            return false;
+        }
+        let [c1, c2] = HoldemRange::from_hand_idx(idx);
+        is_short_deck_card(c1) && is_short_deck_card(c2)
+    }
+
+    /// Only iterates on combos with weight > 0
+    pub fn for_each_weighted<F>(&self, f: F)
+    where
+        F: FnMut(f32, usize),
+    {
+        self.range.for_each_weighted(f)
+    }
+
+    /// The underlying 1326 weights
+    pub fn as_holdem_range(&self) -> &HoldemRange {
+        &self.range
+    }
+}
+
+impl TryFrom<HoldemRange> for ShortDeckRange {
+    type Error = String;
+
+    /// Fails if any combo with weight uses a deuce through five
+    fn try_from(range: HoldemRange) -> Result<Self, Self::Error> {
+        let mut invalid = None;
+        range.for_each_weighted(|_weight, idx| {
+            if invalid.is_none() && !Self::is_valid_hand_idx(idx) {
+                invalid = Some(idx);
+            }
+        });
+        match invalid {
+            Some(idx) => Err(format!("Hand index {idx} is not a Short Deck combo")),
+            None => Ok(Self { range }),
+        }
+    }
+}
@@ -17,3 +17,12 @@ pub struct EquityResult {
     pub(crate) hand_idx: usize,
     pub(crate) equity: Equity,
 }
+
+/// Best five card hand and its rank
+/// For Omaha the first 2 cards are the hole cards used and the last 3 the board cards
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BestHand {
+    pub(crate) cards: [u8; 5],
+    pub(crate) rank: i32,
+}
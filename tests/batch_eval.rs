@@ -0,0 +1,68 @@
+use std::sync::OnceLock;
+
+#[cfg(feature = "compact")]
+use poker_wasm::{CompactEvaluator, HandEvaluator};
+use poker_wasm::{parse_cards, EquityCalculator};
+
+fn calculator() -> &'static EquityCalculator {
+    static CALCULATOR: OnceLock<EquityCalculator> = OnceLock::new();
+    CALCULATOR.get_or_init(EquityCalculator::with_generated_tables)
+}
+
+fn cards(text: &str) -> Vec<u8> {
+    parse_cards(text).unwrap()
+}
+
+#[test]
+fn batches_match_single_evaluation() {
+    let calculator = calculator();
+    let board = cards("Qh7d2h5c9s");
+    let hands = cards("AsKs QcQd 8h6h 3c4d");
+
+    let ranks = calculator.eval_holdem_batch(&board, &hands).unwrap();
+    assert_eq!(ranks.len(), 4);
+    for (hand, rank) in hands.chunks(2).zip(&ranks) {
+        let seven = [&board[..], hand].concat();
+        assert_eq!(calculator.eval_holdem(&seven).unwrap(), *rank);
+        #[cfg(feature = "compact")]
+        assert_eq!(CompactEvaluator::new().eval(&seven), *rank);
+    }
+
+    let omaha = cards("AsKsJhTh 8c8d6s3s");
+    let ranks = calculator.eval_omaha_batch(&board, &omaha, 4).unwrap();
+    for (hand, rank) in omaha.chunks(4).zip(&ranks) {
+        assert_eq!(calculator.eval_omaha(hand, &board).unwrap(), *rank);
+    }
+    assert!(calculator.eval_holdem_batch(&board, &[]).unwrap().is_empty());
+}
+
+#[test]
+fn batches_reject_invalid_cards() {
+    let calculator = calculator();
+    let board = cards("Qh7d2h5c9s");
+    let batch = |hands: &[u8]| calculator.eval_holdem_batch(&board, hands).unwrap_err();
+
+    assert_eq!(batch(&[51, 50, 52, 0]), "Invalid card 52, cards must be 0-51");
+    assert_eq!(batch(&[51, 51]), "Duplicate card As");
+    assert_eq!(batch(&[51, 50, 3]), "Hands must contain 2 cards each");
+    assert_eq!(batch(&cards("AsKsQhJc")), "Hand QhJc shares a card with the board");
+    assert_eq!(
+        calculator.eval_holdem_batch(&[42, 42, 20], &[51, 50]).unwrap_err(),
+        "Duplicate card Qh"
+    );
+    assert_eq!(
+        calculator.eval_holdem_batch(&board[..2], &[51, 50]).unwrap_err(),
+        "Board must have 3, 4, or 5 cards"
+    );
+
+    assert_eq!(
+        calculator.eval_omaha_batch(&board, &[51, 50, 49, 99], 4).unwrap_err(),
+        "Invalid card 99, cards must be 0-51"
+    );
+    assert_eq!(
+        calculator.eval_omaha_batch(&board, &cards("AsKsJhQh"), 4).unwrap_err(),
+        "Hand shares a card with the board"
+    );
+    assert_eq!(calculator.eval_omaha_batch(&board, &[51, 50, 49], 3).unwrap_err(), "Omaha hand must be 4, 5, or 6 cards, got 3");
+    assert_eq!(calculator.eval_holdem(&cards("AsKs")).unwrap_err(), "Hand must have 5-7 cards, got 2");
+}
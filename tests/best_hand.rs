@@ -0,0 +1,79 @@
+use std::sync::OnceLock;
+
+use poker_wasm::omaha::best_omaha_hand;
+use poker_wasm::{best_five_cards, best_holdem_hand, parse_cards, TwoPlusTwoEvaluator};
+
+fn evaluator() -> &'static TwoPlusTwoEvaluator {
+    static EVALUATOR: OnceLock<TwoPlusTwoEvaluator> = OnceLock::new();
+    EVALUATOR.get_or_init(TwoPlusTwoEvaluator::generated)
+}
+
+fn cards(text: &str) -> Vec<u8> {
+    parse_cards(text).unwrap()
+}
+
+fn sorted(mut cards: Vec<u8>) -> Vec<u8> {
+    cards.sort_unstable();
+    cards
+}
+
+#[test]
+fn picks_the_made_hand() {
+    let evaluator = evaluator();
+
+    // the straight, not the pair of twos
+    let best = best_holdem_hand(evaluator, &cards("5c6d").try_into().unwrap(), &cards("8c7d2h4s2c")).unwrap();
+    assert_eq!(sorted(best.cards()), sorted(cards("5c6d8c7d4s")));
+
+    // five hearts beat the straight
+    let best = best_holdem_hand(evaluator, &cards("AhTh").try_into().unwrap(), &cards("9h8h2hJsQd")).unwrap();
+    assert_eq!(sorted(best.cards()), sorted(cards("AhTh9h8h2h")));
+
+    // the board plays when neither hole card helps
+    let board = cards("AsKsQsJsTs");
+    let best = best_holdem_hand(evaluator, &cards("2c3d").try_into().unwrap(), &board).unwrap();
+    assert_eq!(best.cards(), board);
+    assert_eq!(best_five_cards(evaluator, &board).unwrap(), best);
+}
+
+#[test]
+fn omaha_uses_exactly_two_hole_cards() {
+    let evaluator = evaluator();
+
+    // four hearts on board and one in hand is no flush, the best is two pair using the aces
+    let board: [u8; 5] = cards("2h5h9hKhKc").try_into().unwrap();
+    let best = best_omaha_hand(evaluator, &cards("AhAc7s8d"), &board).unwrap();
+    assert_eq!(sorted(best.cards()[..2].to_vec()), sorted(cards("AhAc")));
+    let hold_em = best_holdem_hand(evaluator, &cards("AhAc").try_into().unwrap(), &board).unwrap();
+    assert!(best.rank() < hold_em.rank());
+
+    // a board straight doesn't count without two hole cards in it
+    let board: [u8; 5] = cards("5c6d7h8s9c").try_into().unwrap();
+    let best = best_omaha_hand(evaluator, &cards("TcJdAsAh"), &board).unwrap();
+    assert_eq!(sorted(best.cards()), sorted(cards("TcJd7h8s9c")));
+    for card in &best.cards()[2..] {
+        assert!(board.contains(card));
+    }
+}
+
+#[test]
+fn rejects_invalid_cards() {
+    let evaluator = evaluator();
+    let board = cards("2h5h9hKhKc");
+
+    assert_eq!(best_five_cards(evaluator, &board[..4]).unwrap_err(), "Need 5-7 cards, got 4");
+    assert_eq!(best_holdem_hand(evaluator, &[52, 0], &board).unwrap_err(), "Invalid card 52, cards must be 0-51");
+    assert_eq!(best_holdem_hand(evaluator, &[0, 0], &board).unwrap_err(), "Duplicate card 2c");
+    assert_eq!(best_holdem_hand(evaluator, &[0, 1], &board[..2]).unwrap_err(), "Board must have 3, 4, or 5 cards");
+
+    let board: [u8; 5] = board.try_into().unwrap();
+    assert_eq!(
+        best_omaha_hand(evaluator, &cards("AhAc7s"), &board).unwrap_err(),
+        "Omaha hand must be 4, 5, or 6 cards, got 3"
+    );
+    assert_eq!(
+        best_omaha_hand(evaluator, &cards("AhAc7sKh"), &board).unwrap_err(),
+        "Hand shares a card with the board"
+    );
+    assert_eq!(best_omaha_hand(evaluator, &[0, 1, 2, 99], &board).unwrap_err(), "Invalid card 99, cards must be 0-51");
+}
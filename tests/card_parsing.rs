@@ -0,0 +1,30 @@
+use poker_wasm::{format_cards, parse_board, parse_cards, parse_hand};
+
+#[test]
+fn parses_any_separator_and_case() {
+    assert_eq!(parse_cards("2cAs").unwrap(), vec![0, 51]);
+    assert_eq!(parse_cards("AhKd7c").unwrap(), parse_cards(" ah, KD 7C ").unwrap());
+    assert_eq!(parse_cards("").unwrap(), Vec::<u8>::new());
+    assert_eq!(format_cards(&parse_cards("ah kd,7c").unwrap()).unwrap(), "AhKd7c");
+    assert_eq!(parse_board("Qh7d2h5c").unwrap().len(), 4);
+    assert_eq!(parse_hand("AsAhKsKh").unwrap().len(), 4);
+}
+
+#[test]
+fn parse_errors() {
+    let error = |text: &str| parse_cards(text).unwrap_err();
+
+    assert_eq!(error("AhKd7"), "Missing suit for '7' at position 4");
+    assert_eq!(error("Ah Xd"), "Invalid card 'Xd' at position 3");
+    assert_eq!(error("AhKz"), "Invalid card 'Kz' at position 2");
+    assert_eq!(error("1h"), "Invalid card '1h' at position 0");
+    assert_eq!(error("AhKdah"), "Duplicate card Ah");
+
+    assert_eq!(parse_board("AhKd").unwrap_err(), "Board must have 3, 4, or 5 cards, got 2");
+    assert_eq!(parse_board("AhKd7c6c5c4c").unwrap_err(), "Board must have 3, 4, or 5 cards, got 6");
+    assert_eq!(parse_hand("AhKd7c").unwrap_err(), "Hand must have 2, 4, 5, or 6 cards, got 3");
+    assert_eq!(parse_hand("AhAh").unwrap_err(), "Duplicate card Ah");
+
+    assert_eq!(format_cards(&[0, 52]).unwrap_err(), "Invalid card 52, cards must be 0-51");
+    assert_eq!(format_cards(&[7, 7]).unwrap_err(), "Duplicate card 3s");
+}
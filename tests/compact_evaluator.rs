@@ -0,0 +1,47 @@
+#![cfg(feature = "compact")]
+
+use poker_wasm::{CompactEvaluator, HandEvaluator, TwoPlusTwoEvaluator};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+#[test]
+fn compact_ranks_match_two_plus_two() {
+    let two_plus_two = TwoPlusTwoEvaluator::generated();
+    let compact = CompactEvaluator::new();
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut deck: Vec<u8> = (0..52).collect();
+
+    for _ in 0..200_000 {
+        deck.shuffle(&mut rng);
+        for num_cards in 5..=7 {
+            let cards = &deck[..num_cards];
+            assert_eq!(compact.eval(cards), two_plus_two.eval(cards), "{cards:?}");
+        }
+
+        // the board evaluators take the same path as the equity code
+        let (board, hand) = deck[..7].split_at(5);
+        assert_eq!(compact.board_eval(board)(hand), two_plus_two.board_eval(board)(hand), "{board:?} {hand:?}");
+    }
+}
+
+#[cfg(feature = "compact")]
+#[test]
+fn compact_calculator_matches_table_calculator() {
+    use poker_wasm::{parse_cards, EquityCalculator, HoldemRange};
+
+    let hero: HoldemRange = "TT+, AQs+, KQo".parse().unwrap();
+    let villain: HoldemRange = "99+, AK, QJs".parse().unwrap();
+    let board = parse_cards("Qh7d2h5c9s").unwrap();
+
+    let mut compact = EquityCalculator::compact();
+    let mut table = EquityCalculator::with_generated_tables();
+    for calculator in [&mut compact, &mut table] {
+        calculator.set_hero_range(hero.clone());
+        calculator.set_vs_range(villain.clone());
+    }
+    assert_eq!(
+        compact.leaf_equity_vs_range(&board, None).unwrap(),
+        table.leaf_equity_vs_range(&board, None).unwrap()
+    );
+}
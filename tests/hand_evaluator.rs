@@ -0,0 +1,47 @@
+use poker_wasm::holdem::calculate_equity_vs_range;
+use poker_wasm::omaha::calculate_omaha_equity_vs_range;
+#[cfg(feature = "compact")]
+use poker_wasm::CompactEvaluator;
+use poker_wasm::{parse_cards, HandEvaluator, HoldemRange, OmahaRange, TwoPlusTwoEvaluator};
+
+/// An outside backend that only supplies board_eval, counting the boards it prepares
+struct Counting<'t> {
+    inner: &'t TwoPlusTwoEvaluator,
+    boards: std::cell::Cell<usize>,
+}
+
+impl HandEvaluator for Counting<'_> {
+    fn board_eval<'a>(&'a self, board: &'a [u8]) -> impl Fn(&[u8]) -> i32 + 'a {
+        self.boards.set(self.boards.get() + 1);
+        self.inner.board_eval(board)
+    }
+}
+
+#[test]
+fn evaluators_agree_through_the_trait() {
+    let table = TwoPlusTwoEvaluator::generated();
+    let counting = Counting { inner: &table, boards: Default::default() };
+
+    // the default eval goes through board_eval
+    let cards = parse_cards("AsAhKdKcQs7h2c").unwrap();
+    assert_eq!(counting.eval(&cards), table.eval(&cards));
+    assert_eq!(table.data().eval(&cards), table.eval(&cards));
+    assert_eq!(counting.boards.get(), 1);
+
+    let hero: HoldemRange = "TT+, AQs+, KQo".parse().unwrap();
+    let villain: HoldemRange = "99+, AK, QJs, 76s".parse().unwrap();
+    let turn = parse_cards("Qh7d2h5c").unwrap();
+    let expected = calculate_equity_vs_range(&table, &hero, &villain, &turn, &[]).unwrap();
+    assert_eq!(calculate_equity_vs_range(table.data(), &hero, &villain, &turn, &[]).unwrap(), expected);
+    #[cfg(feature = "compact")]
+    assert_eq!(calculate_equity_vs_range(&CompactEvaluator::new(), &hero, &villain, &turn, &[]).unwrap(), expected);
+    assert_eq!(calculate_equity_vs_range(&counting, &hero, &villain, &turn, &[]).unwrap(), expected);
+    assert!(counting.boards.get() > 1);
+
+    let range = OmahaRange::parse("AA**, KKQQ", 4).unwrap();
+    let hand = parse_cards("JsTs9h8h").unwrap();
+    let expected = calculate_omaha_equity_vs_range(&table, &hand, &range, &turn, &[]).unwrap();
+    #[cfg(feature = "compact")]
+    assert_eq!(calculate_omaha_equity_vs_range(&CompactEvaluator::new(), &hand, &range, &turn, &[]).unwrap(), expected);
+    assert_eq!(calculate_omaha_equity_vs_range(&counting, &hand, &range, &turn, &[]).unwrap(), expected);
+}
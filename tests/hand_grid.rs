@@ -0,0 +1,75 @@
+use poker_wasm::holdem::calculate_leaf_equity;
+use poker_wasm::{
+    aggregate_by_class, hand_class_combos, hand_class_index, hand_class_name, parse_cards, HoldemRange,
+    TwoPlusTwoEvaluator, NUM_HAND_CLASSES,
+};
+
+fn class_of(name: &str) -> usize {
+    (0..NUM_HAND_CLASSES).find(|&idx| hand_class_name(idx).unwrap() == name).unwrap()
+}
+
+#[test]
+fn class_names_and_indices_agree() {
+    for (idx, name) in [(0, "AA"), (1, "AKs"), (13, "AKo"), (12, "A2s"), (156, "A2o"), (14, "KK"), (168, "22")] {
+        assert_eq!(hand_class_name(idx).unwrap(), name);
+    }
+    assert_eq!(hand_class_name(169).unwrap_err(), "Hand class index 169 must be below 169");
+
+    let mut seen = 0;
+    for class_idx in 0..NUM_HAND_CLASSES {
+        let name = hand_class_name(class_idx).unwrap();
+        let combos = hand_class_combos(class_idx);
+        let expected = match name.len() {
+            2 => 6,
+            _ if name.ends_with('s') => 4,
+            _ => 12,
+        };
+        assert_eq!(combos.len(), expected, "{name}");
+        assert!(combos.iter().all(|&idx| hand_class_index(idx) == class_idx));
+
+        // the name parses back to exactly the cell's combos
+        let range: HoldemRange = name.parse().unwrap();
+        let mut parsed = Vec::new();
+        range.for_each_weighted(|_, idx| parsed.push(idx));
+        parsed.sort_unstable();
+        assert_eq!(parsed, combos, "{name}");
+        seen += combos.len();
+    }
+    assert_eq!(seen, 1326);
+
+    let ah_kh = parse_cards("AhKh").unwrap();
+    assert_eq!(hand_class_index(HoldemRange::get_hand_idx([ah_kh[0], ah_kh[1]])), class_of("AKs"));
+}
+
+#[test]
+fn grid_round_trips() {
+    let range: HoldemRange = "QQ:0.5, AKs, 72o".parse().unwrap();
+    let grid = range.to_grid();
+    assert_eq!(grid.len(), NUM_HAND_CLASSES);
+    assert_eq!((grid[class_of("QQ")], grid[class_of("AKs")], grid[class_of("72o")]), (0.5, 1.0, 1.0));
+    assert_eq!(grid.iter().filter(|&&weight| weight > 0.0).count(), 3);
+    assert_eq!(HoldemRange::from_grid(&grid).unwrap(), range);
+
+    // a single combo is averaged over its cell
+    let mut one_combo = HoldemRange::new();
+    one_combo.set_hand(&parse_cards("AhKh").unwrap(), 1.0).unwrap();
+    assert_eq!(one_combo.to_grid()[class_of("AKs")], 0.25);
+
+    assert_eq!(HoldemRange::from_grid(&[1.0; 13]).unwrap_err(), "Grid must have 169 weights, got 13");
+}
+
+#[test]
+fn results_aggregate_by_class() {
+    let hero: HoldemRange = "AA, KK:0.5".parse().unwrap();
+    let villain: HoldemRange = "QQ".parse().unwrap();
+    let board = parse_cards("2c3d7h8s9c").unwrap();
+    let results = calculate_leaf_equity(&TwoPlusTwoEvaluator::generated(), &hero, &villain, &board, &[]).unwrap();
+
+    let classes = aggregate_by_class(&results, &hero);
+    assert_eq!(classes.len(), NUM_HAND_CLASSES);
+    let aces = classes[class_of("AA")];
+    assert_eq!((aces.class_idx(), aces.combos(), aces.weight(), aces.equity()), (class_of("AA"), 6, 6.0, 1.0));
+    let kings = classes[class_of("KK")];
+    assert_eq!((kings.combos(), kings.weight(), kings.equity()), (6, 3.0, 1.0));
+    assert_eq!(classes[class_of("QQ")].combos(), 0);
+}
@@ -0,0 +1,42 @@
+use std::sync::OnceLock;
+
+use poker_wasm::omaha::eval_omaha_hand;
+use poker_wasm::{describe_rank, parse_cards, HandCategory, HandEvaluator, HandRankInfo, TwoPlusTwoEvaluator};
+
+fn evaluator() -> &'static TwoPlusTwoEvaluator {
+    static EVALUATOR: OnceLock<TwoPlusTwoEvaluator> = OnceLock::new();
+    EVALUATOR.get_or_init(TwoPlusTwoEvaluator::generated)
+}
+
+fn rank(cards: &str) -> i32 {
+    evaluator().eval(&parse_cards(cards).unwrap())
+}
+
+#[test]
+fn describes_ranks() {
+    assert_eq!(describe_rank(13146).as_deref(), Some("Two Pair, Aces and Kings"));
+    assert_eq!(rank("AsAhKdKcQs"), 13146);
+
+    let info = HandRankInfo::from_rank(13146).unwrap();
+    assert_eq!(info.category(), HandCategory::TwoPair);
+    assert_eq!(info.rank_in_category(), 858);
+    assert_eq!(info.ranks(), &[12, 11, 10]);
+
+    for (cards, description) in [
+        ("Kh9h7h4h2h", "Flush, King high"),
+        ("Ah2c3d4s5h", "Straight, Five high"),
+        ("7c7d7hKsKd", "Full House, Sevens full of Kings"),
+        ("9s9c4d3h2c", "One Pair, Nines"),
+        ("AsKsQsJsTs", "Straight Flush, Ace high"),
+    ] {
+        assert_eq!(describe_rank(rank(cards)).as_deref(), Some(description), "{cards}");
+    }
+
+    // the Omaha path returns ranks in the same format
+    let board: [u8; 5] = parse_cards("Kh9h7h4c2c").unwrap().try_into().unwrap();
+    let omaha = eval_omaha_hand(evaluator(), &parse_cards("AhQhAsAd").unwrap(), &board);
+    assert_eq!(describe_rank(omaha).as_deref(), Some("Flush, Ace high"));
+
+    assert_eq!(describe_rank(0), None);
+    assert!(HandRankInfo::from_rank(-1).is_none());
+}
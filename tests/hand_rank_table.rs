@@ -0,0 +1,51 @@
+use std::sync::OnceLock;
+
+use poker_wasm::{
+    fnv1a_64, generate_hand_ranks, validate_hand_ranks, RankTableError, HAND_RANKS_FNV1A, HAND_RANKS_LEN,
+};
+
+/// The generated table, built once for every test in this file
+fn generated() -> &'static [u8] {
+    static TABLE: OnceLock<Vec<u8>> = OnceLock::new();
+    TABLE.get_or_init(generate_hand_ranks)
+}
+
+#[test]
+fn generated_table_matches_hand_ranks_dat() {
+    let table = generated();
+    match std::fs::read(format!("{}/HandRanks.dat", env!("CARGO_MANIFEST_DIR"))) {
+        Ok(data) => assert!(table == data.as_slice(), "generated table differs from HandRanks.dat"),
+        // HandRanks.dat isn't checked in, its fingerprint is
+        Err(_) => {
+            assert_eq!(fnv1a_64(table), HAND_RANKS_FNV1A);
+            assert_eq!(validate_hand_ranks(table), Ok(()));
+        }
+    }
+}
+
+#[test]
+fn validation_rejects_truncated_and_corrupted_tables() {
+    let table = generated();
+    assert_eq!(validate_hand_ranks(table), Ok(()));
+
+    let truncated = &table[..table.len() - 4];
+    assert_eq!(
+        validate_hand_ranks(truncated),
+        Err(RankTableError::InvalidLength { expected: HAND_RANKS_LEN, actual: HAND_RANKS_LEN - 4 })
+    );
+
+    // right size, but every entry is zero so the royal flush spot check fails first
+    let zeroed = vec![0u8; HAND_RANKS_LEN];
+    assert!(matches!(
+        validate_hand_ranks(&zeroed),
+        Err(RankTableError::WrongEvaluation { expected: 36874, actual: 0, .. })
+    ));
+
+    // the spot checks never read the last entry
+    let mut corrupted = table.to_vec();
+    *corrupted.last_mut().unwrap() ^= 1;
+    assert_eq!(
+        validate_hand_ranks(&corrupted),
+        Err(RankTableError::ChecksumMismatch { expected: HAND_RANKS_FNV1A, actual: fnv1a_64(&corrupted) })
+    );
+}
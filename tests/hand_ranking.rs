@@ -0,0 +1,43 @@
+use poker_wasm::{hand_class_name, HandOrdering, HoldemRange};
+
+/// Class names from best to worst
+fn ranked_names(ordering: &HandOrdering<HoldemRange>) -> Vec<String> {
+    HoldemRange::hand_ranking(ordering)
+        .into_iter()
+        .map(|(class_idx, _)| hand_class_name(class_idx).unwrap())
+        .collect()
+}
+
+fn position(names: &[String], name: &str) -> usize {
+    names.iter().position(|n| n == name).unwrap()
+}
+
+#[test]
+fn vs_random_orders_known_hands() {
+    let names = ranked_names(&HandOrdering::VsRandom);
+    assert_eq!(names.len(), 169);
+    assert_eq!(&names[..3], ["AA", "KK", "QQ"]);
+    assert_eq!(names.last().unwrap(), "32o");
+
+    let pairs = ["AA", "KK", "QQ", "JJ", "TT", "99", "88", "77", "66", "55", "44", "33", "22"];
+    for pair in pairs.windows(2) {
+        assert!(position(&names, pair[0]) < position(&names, pair[1]), "{} vs {}", pair[0], pair[1]);
+    }
+    for (better, worse) in [("AKs", "AKo"), ("AKo", "AQo"), ("KQs", "KQo"), ("T9s", "T9o"), ("A2s", "32s")] {
+        assert!(position(&names, better) < position(&names, worse), "{better} vs {worse}");
+    }
+
+    // exact, so the equities are the known all-in numbers vs a random hand
+    let ranking = HoldemRange::hand_ranking(&HandOrdering::VsRandom);
+    assert!((ranking[0].1 - 0.8520).abs() < 1e-4, "{}", ranking[0].1);
+}
+
+#[test]
+fn top_percent_takes_whole_classes_from_the_top() {
+    // AA and KK are exactly 12 of the 1326 combos
+    let range = HoldemRange::top_percent(12.0 / 1326.0 * 100.0, &HandOrdering::VsRandom).unwrap();
+    let top_two: HoldemRange = "KK+".parse().unwrap();
+    for idx in 0..1326 {
+        assert!((range.get_weight(idx) - top_two.get_weight(idx)).abs() < 1e-4, "combo {idx}");
+    }
+}
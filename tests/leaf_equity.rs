@@ -0,0 +1,161 @@
+use std::sync::OnceLock;
+
+use poker_wasm::holdem::{calculate_leaf_equity, LeafEquityWorkspace};
+use poker_wasm::omaha::{calculate_omaha_equity_vs_range, calculate_omaha_leaf_equity};
+use poker_wasm::omaha_hilo::calculate_omaha_hilo_leaf_equity;
+use poker_wasm::{cards_to_mask, parse_cards, HandEvaluator, HoldemRange, OmahaRange, TwoPlusTwoEvaluator};
+
+fn evaluator() -> &'static TwoPlusTwoEvaluator {
+    static EVALUATOR: OnceLock<TwoPlusTwoEvaluator> = OnceLock::new();
+    EVALUATOR.get_or_init(TwoPlusTwoEvaluator::generated)
+}
+
+/// Villain weight hero beats, ties and loses to, checking every villain combo
+fn count_outcomes(hero: [u8; 2], villain: &HoldemRange, board: &[u8], dead: &[u8]) -> [f32; 3] {
+    let evaluator = evaluator();
+    let board_eval = evaluator.board_eval(board);
+    let blocked = cards_to_mask(board) | cards_to_mask(dead) | cards_to_mask(&hero);
+    let hero_rank = board_eval(&hero);
+
+    let mut outcomes = [0.0f32; 3];
+    villain.for_each_weighted(|weight, idx| {
+        let combo = HoldemRange::from_hand_idx(idx);
+        if cards_to_mask(&combo) & blocked != 0 {
+            return;
+        }
+        let outcome = match hero_rank.cmp(&board_eval(&combo)) {
+            std::cmp::Ordering::Greater => 0,
+            std::cmp::Ordering::Equal => 1,
+            std::cmp::Ordering::Less => 2,
+        };
+        outcomes[outcome] += weight;
+    });
+    outcomes
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 1e-3, "{actual} != {expected}");
+}
+
+#[test]
+fn leaf_equity_matches_counting() {
+    let hero: HoldemRange = "22+, A2s+, K9s+, ATo+, KJo, 65s:0.5".parse().unwrap();
+    let villain: HoldemRange = "55+, A5s+, KTs+, AJo+, 76s:0.25, 87s".parse().unwrap();
+    // a paired board so many combos tie, and one with a dead card
+    for (board, dead) in [("7h7d2c9s9c", ""), ("Ah8h5c4d2s", "Kh")] {
+        let (board, dead) = (parse_cards(board).unwrap(), parse_cards(dead).unwrap());
+        let results = calculate_leaf_equity(evaluator(), &hero, &villain, &board, &dead).unwrap();
+
+        let blocked = cards_to_mask(&board) | cards_to_mask(&dead);
+        let live_heroes = (0..1326)
+            .filter(|&idx| hero.get_weight(idx) > 0.0)
+            .filter(|&idx| cards_to_mask(&HoldemRange::from_hand_idx(idx)) & blocked == 0)
+            .count();
+        assert_eq!(results.len(), live_heroes);
+
+        for result in &results {
+            let [win, tie, lose] = count_outcomes(HoldemRange::from_hand_idx(result.hand_idx()), &villain, &board, &dead);
+            let equity = result.equity();
+            assert_close(equity.win(), win);
+            assert_close(equity.tie(), tie);
+            assert_close(equity.lose(), lose);
+        }
+    }
+}
+
+#[test]
+fn workspace_reuse_matches_fresh_calls() {
+    let hero: HoldemRange = "TT+, AQs+, KQo".parse().unwrap();
+    let villain: HoldemRange = "99+, AK, QJs".parse().unwrap();
+    let flop = parse_cards("Qh7d2h").unwrap();
+    let evaluator = evaluator();
+
+    let mut workspace = LeafEquityWorkspace::new(&hero, &villain, cards_to_mask(&flop));
+    for runout in [[3u8, 4], [3, 51], [50, 51], [12, 40]] {
+        let board = [flop[0], flop[1], flop[2], runout[0], runout[1]];
+        let fresh = calculate_leaf_equity(evaluator, &hero, &villain, &board, &[]).unwrap();
+        assert_eq!(workspace.leaf_equity(evaluator, &board), &fresh[..]);
+    }
+}
+
+#[test]
+fn dead_cards_are_validated_and_block_combos() {
+    let evaluator = evaluator();
+    let hero: HoldemRange = "AA, KK".parse().unwrap();
+    let villain: HoldemRange = "KK".parse().unwrap();
+    let board = parse_cards("Qh7d2h5c9s").unwrap();
+
+    for (dead, err) in [
+        (vec![64], "Invalid card 64, cards must be 0-51"),
+        (vec![55], "Invalid card 55, cards must be 0-51"),
+        (vec![51, 51], "Duplicate card As"),
+        (parse_cards("Qh").unwrap(), "Dead cards can't be on the board"),
+    ] {
+        assert_eq!(calculate_leaf_equity(evaluator, &hero, &villain, &board, &dead).unwrap_err(), err);
+    }
+
+    // hero combos holding a dead card are left out, and so are villain's
+    let dead = parse_cards("Ks").unwrap();
+    let results = calculate_leaf_equity(evaluator, &hero, &villain, &board, &dead).unwrap();
+    assert_eq!(results.len(), 6 + 3);
+    for result in results.iter().filter(|result| result.combo().iter().all(|&card| card / 4 == 12)) {
+        // three of the six kings combos are left
+        let equity = result.equity();
+        assert_eq!((equity.win(), equity.tie(), equity.lose()), (3.0, 0.0, 0.0));
+    }
+}
+
+#[test]
+fn dead_cards_never_come() {
+    let evaluator = evaluator();
+    let range = OmahaRange::parse("AA**, KKQQ", 4).unwrap();
+    let hero = parse_cards("JsTs9h8h").unwrap();
+    let flop = parse_cards("Qh7d2h").unwrap();
+    let dead = parse_cards("Ah2c").unwrap();
+
+    let runouts = calculate_omaha_equity_vs_range(evaluator, &hero, &range, &flop, &dead).unwrap();
+    // every turn and river from the 52 - 3 - 4 - 2 cards left
+    assert_eq!(runouts.len(), 43 * 42 / 2);
+    for runout in &runouts {
+        assert!(runout.board().iter().all(|card| !dead.contains(card)));
+    }
+
+    let river = parse_cards("Qh7d2h3s4s").unwrap().try_into().unwrap();
+    assert_eq!(
+        calculate_omaha_leaf_equity(evaluator, &hero, &range, &river, &[64]).unwrap_err(),
+        "Invalid card 64, cards must be 0-51"
+    );
+    assert_eq!(
+        calculate_omaha_leaf_equity(evaluator, &hero, &range, &river, &parse_cards("Js").unwrap()).unwrap_err(),
+        "Hero hand holds a dead card"
+    );
+}
+
+#[test]
+#[should_panic(expected = "board must be 3-5 cards")]
+fn workspace_rejects_short_boards() {
+    let range: HoldemRange = "AA".parse().unwrap();
+    let board = parse_cards("Qh7d").unwrap();
+    LeafEquityWorkspace::new(&range, &range, cards_to_mask(&board)).leaf_equity(evaluator(), &board);
+}
+
+#[test]
+fn hilo_leaf_checks_its_cards() {
+    let evaluator = evaluator();
+    let range = OmahaRange::parse("AA**, KKQQ", 4).unwrap();
+    let hero = parse_cards("JsTs9h8h").unwrap();
+    let river: [u8; 5] = parse_cards("Qh7d2h3s4s").unwrap().try_into().unwrap();
+    let leaf = |hero: &[u8], dead: &[u8]| calculate_omaha_hilo_leaf_equity(evaluator, hero, &range, &river, dead);
+
+    assert_eq!(leaf(&hero, &[64]).unwrap_err(), "Invalid card 64, cards must be 0-51");
+    assert_eq!(leaf(&hero, &[51, 51]).unwrap_err(), "Duplicate card As");
+    assert_eq!(leaf(&hero, &parse_cards("Qh").unwrap()).unwrap_err(), "Dead cards can't be on the board");
+    assert_eq!(leaf(&hero, &parse_cards("Js").unwrap()).unwrap_err(), "Hero hand holds a dead card");
+    assert_eq!(leaf(&hero[..3], &[]).unwrap_err(), "Omaha hand must be 4, 5, or 6 cards, got 3");
+    assert_eq!(leaf(&parse_cards("QhTs9h8h").unwrap(), &[]).unwrap_err(), "Hero hand and board share a card");
+
+    // a dead ace blocks villain's aces
+    let all = leaf(&hero, &[]).unwrap().equity().total();
+    let blocked = leaf(&hero, &parse_cards("Ac").unwrap()).unwrap().equity().total();
+    assert!(blocked < all);
+}
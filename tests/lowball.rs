@@ -0,0 +1,61 @@
+use poker_wasm::{eval_ace_to_five, eval_deuce_to_seven, parse_cards};
+
+fn hand(text: &str) -> [u8; 5] {
+    parse_cards(text).unwrap().try_into().unwrap()
+}
+
+fn deuce_to_seven(text: &str) -> i32 {
+    eval_deuce_to_seven(&hand(text)).unwrap()
+}
+
+fn ace_to_five(text: &str) -> i32 {
+    eval_ace_to_five(&hand(text)).unwrap()
+}
+
+#[test]
+fn deuce_to_seven_order() {
+    assert_eq!(deuce_to_seven("7c5d4h3s2c"), 7462);
+    assert_eq!(deuce_to_seven("AsKsQsJsTs"), 1);
+
+    let best_first = [
+        "7c5d4h3s2c",
+        "7c6d4h3s2c",
+        "8c5d4h3s2c",
+        // aces are high, so the wheel is just ace high
+        "Kc8d6h4s3c",
+        "Ac5d4h3s2c",
+        "2c2d7h5s4c",
+        "3c3d7h5s4c",
+        // straights and flushes count against you
+        "6c5d4h3s2c",
+        "7c5c4c3c2c",
+    ];
+    for pair in best_first.windows(2) {
+        assert!(deuce_to_seven(pair[0]) > deuce_to_seven(pair[1]), "{} vs {}", pair[0], pair[1]);
+    }
+    // suits only matter for flushes
+    assert_eq!(deuce_to_seven("9c8d6h4s2c"), deuce_to_seven("9h8s6c4d2h"));
+}
+
+#[test]
+fn ace_to_five_order() {
+    assert_eq!(ace_to_five("5c4d3h2sAc"), 6175);
+    assert_eq!(ace_to_five("KcKdKhKsQc"), 1);
+
+    let best_first = ["5c4d3h2sAc", "6c4d3h2sAc", "6c5d4h3s2c", "7c5d4h3s2c", "Kc8d6h4s3c", "AcAd7h5s4c", "2c2d7h5s4c"];
+    for pair in best_first.windows(2) {
+        assert!(ace_to_five(pair[0]) > ace_to_five(pair[1]), "{} vs {}", pair[0], pair[1]);
+    }
+    // straights and flushes are ignored
+    assert_eq!(ace_to_five("5h4h3h2hAh"), ace_to_five("5c4d3h2sAc"));
+    assert_eq!(ace_to_five("9s8s7s6s5s"), ace_to_five("9c8d7h6s5c"));
+}
+
+#[test]
+fn bad_cards_are_errors() {
+    assert_eq!(eval_deuce_to_seven(&[0, 4, 8, 12, 52]).unwrap_err(), "Invalid card 52, cards must be 0-51");
+    assert_eq!(eval_ace_to_five(&[0, 4, 8, 12, 255]).unwrap_err(), "Invalid card 255, cards must be 0-51");
+    // five of a kind would have no rank
+    assert_eq!(eval_deuce_to_seven(&[0, 1, 2, 3, 0]).unwrap_err(), "Duplicate card 2c");
+    assert_eq!(eval_ace_to_five(&[51, 51, 50, 49, 48]).unwrap_err(), "Duplicate card As");
+}
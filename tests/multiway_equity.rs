@@ -0,0 +1,173 @@
+use std::sync::OnceLock;
+
+use poker_wasm::holdem::calculate_equity_vs_range;
+use poker_wasm::multiway::{
+    calculate_multiway_equity, calculate_multiway_equity_exact, calculate_multiway_equity_monte_carlo,
+    MultiwayEquityResult,
+};
+use poker_wasm::{cards_to_mask, parse_cards, HandEvaluator, HoldemRange, TwoPlusTwoEvaluator};
+
+fn evaluator() -> &'static TwoPlusTwoEvaluator {
+    static EVALUATOR: OnceLock<TwoPlusTwoEvaluator> = OnceLock::new();
+    EVALUATOR.get_or_init(TwoPlusTwoEvaluator::generated)
+}
+
+fn ranges(texts: &[&str]) -> Vec<HoldemRange> {
+    texts.iter().map(|text| text.parse().unwrap()).collect()
+}
+
+/// (player, hand idx) -> (weight, share) by dealing every combo of every range on every river
+fn enumerate_turn(ranges: &[HoldemRange], turn: &[u8]) -> Vec<((usize, usize), [f64; 2])> {
+    let evaluator = evaluator();
+    let mut totals = vec![vec![[0.0f64; 2]; 1326]; ranges.len()];
+    let mut combos: Vec<Vec<(usize, f32)>> = Vec::new();
+    for range in ranges {
+        let mut live = Vec::new();
+        range.for_each_weighted(|weight, idx| {
+            if cards_to_mask(&HoldemRange::from_hand_idx(idx)) & cards_to_mask(turn) == 0 {
+                live.push((idx, weight));
+            }
+        });
+        combos.push(live);
+    }
+
+    let mut deal = vec![0usize; ranges.len()];
+    loop {
+        let hands: Vec<[u8; 2]> = deal.iter().zip(&combos).map(|(&pos, live)| HoldemRange::from_hand_idx(live[pos].0)).collect();
+        let used = hands.iter().fold(cards_to_mask(turn), |used, hand| {
+            if used == u64::MAX || used & cards_to_mask(hand) != 0 { u64::MAX } else { used | cards_to_mask(hand) }
+        });
+        if used != u64::MAX {
+            let weight: f64 = deal.iter().zip(&combos).map(|(&pos, live)| live[pos].1 as f64).product();
+            for river in (0..52u8).filter(|&card| used & (1u64 << card) == 0) {
+                let board = [turn[0], turn[1], turn[2], turn[3], river];
+                let ranks: Vec<i32> = hands.iter().map(|hand| evaluator.board_eval(&board)(hand)).collect();
+                let best = *ranks.iter().max().unwrap();
+                let tied = ranks.iter().filter(|&&rank| rank == best).count() as f64;
+                for (player, (&rank, &pos)) in ranks.iter().zip(&deal).enumerate() {
+                    let total = &mut totals[player][combos[player][pos].0];
+                    total[0] += weight;
+                    total[1] += if rank == best { weight / tied } else { 0.0 };
+                }
+            }
+        }
+
+        // next deal, odometer style
+        let Some(player) = (0..deal.len()).rev().find(|&player| deal[player] + 1 < combos[player].len()) else {
+            break;
+        };
+        deal[player] += 1;
+        deal[player + 1..].fill(0);
+    }
+
+    let mut expected = Vec::new();
+    for (player, totals) in totals.iter().enumerate() {
+        for (idx, &total) in totals.iter().enumerate() {
+            if total[0] > 0.0 {
+                expected.push(((player, idx), total));
+            }
+        }
+    }
+    expected
+}
+
+fn equity_of(results: &[MultiwayEquityResult], player: usize, combo: &str) -> f32 {
+    let cards = parse_cards(combo).unwrap();
+    let idx = HoldemRange::get_hand_idx([cards[0], cards[1]]);
+    results.iter().find(|result| result.player() == player && result.hand_idx() == idx).unwrap().equity()
+}
+
+#[test]
+fn three_way_turn_matches_enumeration() {
+    let ranges = ranges(&["AA, KQs, JTs:0.5", "KK, AQs, QJs", "QQ-TT:0.75, AJs, 98s"]);
+    let turn = parse_cards("Qs9s4c2d").unwrap();
+    let results = calculate_multiway_equity(evaluator(), &ranges, &turn, &[], 1000).unwrap();
+
+    let expected = enumerate_turn(&ranges, &turn);
+    assert_eq!(results.len(), expected.len());
+    for (result, ((player, idx), [weight, share])) in results.iter().zip(expected) {
+        assert_eq!((result.player(), result.hand_idx()), (player, idx));
+        assert!((result.equity() as f64 - share / weight).abs() < 1e-5);
+        assert!(result.win() + result.tie() <= 1.0 + 1e-6);
+    }
+}
+
+#[test]
+fn ties_split_between_tied_players() {
+    // both broadway straights chop, 7c2d drew dead
+    let ranges = ranges(&["AsKs", "AdKd", "7c2d"]);
+    let board = parse_cards("QhJhTc3s2c").unwrap();
+    let results = calculate_multiway_equity(evaluator(), &ranges, &board, &[], 1000).unwrap();
+    assert_eq!(results.len(), 3);
+    for (result, equity) in results.iter().zip([0.5, 0.5, 0.0]) {
+        assert_eq!(result.equity(), equity);
+        assert_eq!(result.tie(), if equity > 0.0 { 1.0 } else { 0.0 });
+        assert_eq!(result.win(), 0.0);
+    }
+}
+
+#[test]
+fn heads_up_matches_equity_vs_range() {
+    let ranges = ranges(&["TT+, AQs+, KQo", "99+, AK, QJs"]);
+    let (flop, dead) = (parse_cards("Qh7d2h").unwrap(), parse_cards("3c").unwrap());
+    let evaluator = evaluator();
+    let multiway = calculate_multiway_equity_exact(evaluator, &ranges, &flop, &dead).unwrap();
+    let mut heads_up = calculate_equity_vs_range(evaluator, &ranges[0], &ranges[1], &flop, &dead).unwrap();
+    // combos blocked by the flop or dead cards come back with nothing counted
+    heads_up.retain(|result| result.equity().win() + result.equity().tie() + result.equity().lose() > 0.0);
+
+    let hero: Vec<_> = multiway.iter().filter(|result| result.player() == 0).collect();
+    assert_eq!(hero.len(), heads_up.len());
+    for (result, expected) in hero.iter().zip(&heads_up) {
+        assert_eq!(result.hand_idx(), expected.hand_idx());
+        let equity = expected.equity();
+        let total = equity.win() + equity.tie() + equity.lose();
+        assert!((result.equity() - (equity.win() + equity.tie() / 2.0) / total).abs() < 1e-4);
+        assert!((result.win() - equity.win() / total).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn monte_carlo_is_close_to_exact() {
+    let ranges = ranges(&["AA, KQs", "KK, AQs, QJs", "QQ-TT, 98s"]);
+    let turn = parse_cards("Qs9s4c2d").unwrap();
+    let evaluator = evaluator();
+    let exact = calculate_multiway_equity_exact(evaluator, &ranges, &turn, &[]).unwrap();
+    let sampled = calculate_multiway_equity_monte_carlo(evaluator, &ranges, &turn, &[], 4000).unwrap();
+
+    assert_eq!(exact.len(), sampled.len());
+    for (exact, sampled) in exact.iter().zip(&sampled) {
+        assert_eq!((exact.player(), exact.hand_idx()), (sampled.player(), sampled.hand_idx()));
+        assert!((exact.equity() - sampled.equity()).abs() < 0.04, "{exact:?} vs {sampled:?}");
+    }
+}
+
+#[test]
+fn preflop_is_sampled_with_card_removal() {
+    let ranges = ranges(&["AA", "KK", "AKs"]);
+    let results = calculate_multiway_equity(evaluator(), &ranges, &[], &[], 500).unwrap();
+    // AKs can only be dealt with aces and kings of the other suits
+    assert_eq!(results.len(), 16);
+    assert!(equity_of(&results, 0, "AcAd") > equity_of(&results, 1, "KcKd"));
+    assert!(equity_of(&results, 2, "AsKs") > 0.0);
+
+    let err = calculate_multiway_equity(evaluator(), &ranges[..1], &[], &[], 500).unwrap_err();
+    assert_eq!(err, "Multiway equity takes 2 to 9 ranges, got 1");
+}
+
+#[test]
+fn too_few_cards_left_is_an_error() {
+    let any_two = "22+, A2s+, K2s+, Q2s+, J2s+, T2s+, 92s+, 82s+, 72s+, 62s+, 52s+, 42s+, 32s, A2o+, K2o+, Q2o+, J2o+, T2o+, 92o+, 82o+, 72o+, 62o+, 52o+, 42o+, 32o";
+    let ranges = ranges(&[any_two, any_two, any_two]);
+    // 9 live cards can't hold three hands and a board
+    let dead: Vec<u8> = (0..43).collect();
+    let err = calculate_multiway_equity(evaluator(), &ranges, &[], &dead, 100).unwrap_err();
+    assert_eq!(err, "Not enough cards left to deal every player and the rest of the board");
+    let err = calculate_multiway_equity_monte_carlo(evaluator(), &ranges, &[], &dead, 100).unwrap_err();
+    assert_eq!(err, "Not enough cards left to deal every player and the rest of the board");
+
+    // exactly enough: 11 live cards, three hands and a five card board
+    let dead: Vec<u8> = (0..41).collect();
+    let results = calculate_multiway_equity_monte_carlo(evaluator(), &ranges, &[], &dead, 20).unwrap();
+    assert!(results.iter().all(|result| (0.0..=1.0).contains(&result.equity())));
+}
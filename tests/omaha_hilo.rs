@@ -0,0 +1,68 @@
+use std::sync::OnceLock;
+
+use poker_wasm::omaha_hilo::{calculate_omaha_hilo_leaf_equity, eval_omaha_low, HiLoEquity};
+use poker_wasm::{parse_cards, OmahaRange, TwoPlusTwoEvaluator};
+
+fn evaluator() -> &'static TwoPlusTwoEvaluator {
+    static EVALUATOR: OnceLock<TwoPlusTwoEvaluator> = OnceLock::new();
+    EVALUATOR.get_or_init(TwoPlusTwoEvaluator::generated)
+}
+
+fn cards(text: &str) -> Vec<u8> {
+    parse_cards(text).unwrap()
+}
+
+fn board(text: &str) -> [u8; 5] {
+    cards(text).try_into().unwrap()
+}
+
+/// Hero's Hi/Lo equity against a single villain hand
+fn heads_up(hero: &str, villain: &str, board_cards: &str) -> HiLoEquity {
+    let range = OmahaRange::from_hands(4, [(cards(villain), 1.0)]).unwrap();
+    calculate_omaha_hilo_leaf_equity(evaluator(), &cards(hero), &range, &board(board_cards), &[])
+        .unwrap()
+        .equity()
+}
+
+/// (scoop, high_only, low_only, quartered, share)
+fn outcome(equity: HiLoEquity) -> (f32, f32, f32, f32, f32) {
+    assert_eq!(equity.total(), 1.0);
+    (equity.scoop(), equity.high_only(), equity.low_only(), equity.quartered(), equity.share())
+}
+
+#[test]
+fn low_needs_two_hole_cards_and_five_low_ranks() {
+    let low_board = board("2c3d7hKsQd");
+    assert!(eval_omaha_low(&cards("As4sKhKc"), &low_board) > eval_omaha_low(&cards("Ad5dQhJc"), &low_board));
+    // one low hole card isn't enough even with three low board cards
+    assert_eq!(eval_omaha_low(&cards("AhKcQcJd"), &low_board), 0);
+    // a paired low card doesn't count twice
+    assert_eq!(eval_omaha_low(&cards("AhAd9c9s"), &low_board), 0);
+    assert_eq!(eval_omaha_low(&cards("As4sKhKc"), &board("KsQdJc9s9d")), 0);
+
+    // 256 minus the mask of low ranks, ace at bit 0
+    assert_eq!(eval_omaha_low(&cards("As2sKhKc"), &board("3c4d5hKsQd")), 256 - 0b11111);
+    assert_eq!(eval_omaha_low(&cards("8s7sKhKc"), &board("6c5d4hKsQd")), 256 - 0b11111000);
+}
+
+#[test]
+fn splits_the_pot() {
+    let low_board = "2c3d7hKsQd";
+    // trip kings and the better low against a pair of queens and a seven-five low
+    assert_eq!(outcome(heads_up("As4sKhKc", "Ad5dQhJc", low_board)), (1.0, 0.0, 0.0, 0.0, 1.0));
+    // same low as villain and the better high, three quarters only counts towards share
+    assert_eq!(outcome(heads_up("As4sKhKc", "AhAd4c4d", low_board)), (0.0, 0.0, 0.0, 0.0, 0.75));
+    // same low, villain's high
+    assert_eq!(outcome(heads_up("Ah4hJcTd", "Ac4cKhKd", low_board)), (0.0, 0.0, 0.0, 1.0, 0.25));
+    assert_eq!(outcome(heads_up("Ah4h9c9d", "KhKcQcJd", low_board)), (0.0, 0.0, 1.0, 0.0, 0.5));
+    assert_eq!(outcome(heads_up("KhKcQcJd", "Ah4h9c9d", low_board)), (0.0, 1.0, 0.0, 0.0, 0.5));
+}
+
+#[test]
+fn high_takes_everything_without_a_low() {
+    let no_low = "KsQdJc9s9d";
+    assert_eq!(outcome(heads_up("KhKc4d5d", "AhAd2c3c", no_low)), (1.0, 0.0, 0.0, 0.0, 1.0));
+    assert_eq!(outcome(heads_up("AhAd2c3c", "KhKc4d5d", no_low)), (0.0, 0.0, 0.0, 0.0, 0.0));
+    // a tied high with no low splits the whole pot
+    assert_eq!(outcome(heads_up("AhTh2c3c", "AdTd4c5c", no_low)), (0.0, 0.0, 0.0, 0.0, 0.5));
+}
@@ -0,0 +1,105 @@
+use poker_wasm::{parse_cards, OmahaRange};
+
+fn parse(notation: &str) -> OmahaRange {
+    OmahaRange::parse(notation, 4).unwrap_or_else(|err| panic!("{notation}: {err}"))
+}
+
+fn weight(range: &OmahaRange, hand: &str) -> Option<f32> {
+    range.weight_of(&parse_cards(hand).unwrap())
+}
+
+fn suit_counts(hand: &[u8]) -> [usize; 4] {
+    let mut counts = [0; 4];
+    for &card in hand {
+        counts[(card % 4) as usize] += 1;
+    }
+    counts
+}
+
+/// `notation` with every hand `keep` rejects removed
+fn filtered(notation: &str, keep: impl Fn(&[u8]) -> bool) -> OmahaRange {
+    let mut range = parse(notation);
+    range.retain(|hand, _| keep(hand));
+    range
+}
+
+#[test]
+fn wildcards_and_variables() {
+    // two aces plus any two cards: 6 * C(48, 2) with exactly two, 4 * 48 with three, and quads
+    let aces = parse("AA**");
+    assert_eq!(aces.len(), 6768 + 192 + 1);
+    assert_eq!(parse("AA"), aces);
+    assert_eq!(parse("AAxx"), aces);
+
+    // "xx" after a rank variable is two more cards, not a suit variable
+    let pairs = parse("RRxx");
+    assert_eq!(pairs, parse("RR**"));
+    assert_eq!(weight(&pairs, "Kc7d7h2s"), Some(1.0));
+    assert_eq!(weight(&pairs, "KcQd7h2s"), None);
+
+    // "x" between ranks ties their suits
+    let suited = parse("AxKx");
+    assert_eq!(weight(&suited, "AhKhQd2c"), Some(1.0));
+    assert_eq!(weight(&suited, "AhKdQc2s"), None);
+    assert_eq!(parse("AxKx"), parse("AsKs, AhKh, AdKd, AcKc"));
+
+    // different rank variables take different ranks
+    assert_eq!(weight(&parse("RROO"), "KcKd7h7s"), Some(1.0));
+    assert_eq!(weight(&parse("RROO"), "KcKdKhKs"), None);
+}
+
+#[test]
+fn macros_filter_hands() {
+    let double_suited = |hand: &[u8]| suit_counts(hand).iter().filter(|&&count| count >= 2).count() >= 2;
+    let single_suited = |hand: &[u8]| suit_counts(hand).iter().filter(|&&count| count >= 2).count() == 1;
+    let rainbow = |hand: &[u8]| suit_counts(hand).iter().all(|&count| count <= 1);
+    let no_pair = |hand: &[u8]| (0..hand.len()).all(|i| (i + 1..hand.len()).all(|j| hand[i] / 4 != hand[j] / 4));
+
+    assert_eq!(parse("KKQ$ds"), filtered("KKQ", double_suited));
+    assert_eq!(parse("KKQ$ss"), filtered("KKQ", single_suited));
+    assert_eq!(parse("KKQ$r"), filtered("KKQ", rainbow));
+    assert_eq!(parse("AK$np"), filtered("AK", no_pair));
+    assert_eq!(parse("AK$np$ds"), filtered("AK", |hand| no_pair(hand) && double_suited(hand)));
+    assert_eq!(weight(&parse("AAKK$ds"), "AsAhKsKh"), Some(1.0));
+}
+
+#[test]
+fn exclusions_and_weights() {
+    let range = parse("RRxx!AAxx");
+    assert_eq!(range.len(), parse("RR").len() - parse("AA").len());
+    assert_eq!(weight(&range, "KcKd2s3h"), Some(1.0));
+    assert_eq!(weight(&range, "AcAdKsKh"), None);
+
+    let range = parse("AA@50, KK:0.25, AAKK@75, QQ!QQJ:0.5, JJ@0");
+    assert_eq!(weight(&range, "AcAd7h2s"), Some(0.5));
+    assert_eq!(weight(&range, "KcKd7h2s"), Some(0.25));
+    assert_eq!(weight(&range, "AcAdKhKs"), Some(0.75));
+    assert_eq!(weight(&range, "QcQd7h2s"), Some(0.5));
+    assert_eq!(weight(&range, "QcQdJh2s"), None);
+    // weight 0 leaves the hands out
+    assert_eq!(weight(&range, "JcJd7h2s"), None);
+}
+
+#[test]
+fn errors_point_at_the_item() {
+    let error = |notation: &str, hand_size| OmahaRange::parse(notation, hand_size).unwrap_err();
+
+    let err = error("AA, ZZ", 4);
+    assert_eq!((err.position, err.token.as_str()), (4, "ZZ"));
+    assert_eq!(err.message, "Invalid card 'Z'");
+
+    let err = error("AA$qq", 4);
+    assert_eq!((err.position, err.message.as_str()), (2, "Unknown macro '$qq'"));
+
+    let err = error("KK, wA", 4);
+    assert_eq!((err.position, err.message.as_str()), (4, "Suit variable 'w' must follow a rank or '*'"));
+
+    assert_eq!(error("AAKKQ", 4).message, "Pattern has 5 cards, hands have 4");
+    assert_eq!(error("AA@half", 4).message, "Invalid weight '@half'");
+
+    // every PLO6 hand is too many to expand
+    let err = error("******", 6);
+    assert_eq!(err.message, "Pattern matches more than 2598960 hands");
+    // 6 * C(48, 4) + 4 * C(48, 3) + C(48, 2) still fits
+    assert_eq!(OmahaRange::parse("AA****", 6).unwrap().len(), 1_237_792);
+}
@@ -0,0 +1,95 @@
+use poker_wasm::{parse_cards, OmahaRange};
+
+fn cards(text: &str) -> Vec<u8> {
+    parse_cards(text).unwrap()
+}
+
+#[test]
+fn hands_are_stored_once_in_canonical_order() {
+    let mut range = OmahaRange::try_new(4).unwrap();
+    range.try_add_hand(&cards("2c7dAsKh"), 0.5).unwrap();
+    range.try_add_hand(&cards("QcQdJhJs"), 1.0).unwrap();
+    // the same hand in another order replaces the weight
+    range.try_add_hand(&cards("KhAs7d2c"), 0.25).unwrap();
+
+    assert_eq!(range.len(), 2);
+    // cards highest first, hands from highest to lowest
+    assert_eq!(range.get_hand(0), Some(&cards("AsKh7d2c")[..]));
+    assert_eq!(range.get_hand(1), Some(&cards("QdQcJsJh")[..]));
+    assert_eq!(range.get_weight(0), Some(0.25));
+
+    // from_hands sorts once and keeps the last copy of a hand
+    let built = OmahaRange::from_hands(
+        4,
+        [(cards("QcQdJhJs"), 1.0), (cards("2c7dAsKh"), 0.5), (cards("7d2cKhAs"), 0.25)],
+    )
+    .unwrap();
+    assert_eq!(built, range);
+}
+
+#[test]
+fn weight_of_finds_hands_in_any_order() {
+    let range = OmahaRange::from_hands(5, [(cards("AsAhKsKh2c"), 0.75)]).unwrap();
+    assert_eq!(range.weight_of(&cards("2cKhAsKsAh")), Some(0.75));
+    assert!(range.contains(&cards("KsKhAsAh2c")));
+    assert_eq!(range.weight_of(&cards("AsAhKsKh3c")), None);
+    // the wrong size or a bad card is never in the range
+    assert_eq!(range.weight_of(&cards("AsAhKsKh")), None);
+    assert_eq!(range.weight_of(&[51, 50, 47, 46, 60]), None);
+}
+
+#[test]
+fn edits_and_remove_blocked() {
+    let mut range = OmahaRange::parse("AAKK, QQJJ, 7654", 4).unwrap();
+    let total = range.len();
+
+    range.set_weight(&cards("KsKhAsAh"), 0.5).unwrap();
+    assert_eq!(range.weight_of(&cards("AsAhKsKh")), Some(0.5));
+    assert!(range.set_weight(&cards("2s2h3s3h"), 0.5).is_err());
+
+    // every hand holding the As or the Qd goes, 3 * 6 AAKK and 6 * 3 QQJJ
+    let blocked = cards("AsQd");
+    let removed = range.remove_blocked(&blocked).unwrap();
+    assert_eq!(removed, 18 + 18);
+    assert_eq!(range.len(), total - removed);
+    assert!(range.iter().all(|(hand, _)| blocked.iter().all(|card| !hand.contains(card))));
+
+    assert!(range.remove_hand(&cards("AhAdKsKh")));
+    assert!(!range.remove_hand(&cards("AhAdKsKh")));
+
+    // bad cards change nothing instead of wrapping to other cards
+    let left = range.len();
+    assert_eq!(range.remove_blocked(&[64]).unwrap_err(), "Invalid card 64, cards must be 0-51");
+    assert_eq!(range.remove_blocked(&[52]).unwrap_err(), "Invalid card 52, cards must be 0-51");
+    assert_eq!(range.remove_blocked(&[0, 0]).unwrap_err(), "Duplicate card 2c");
+    assert_eq!(range.len(), left);
+}
+
+#[test]
+fn try_variants_return_errors() {
+    assert_eq!(OmahaRange::try_new(3).unwrap_err(), "Hand size must be 4, 5, or 6, got 3");
+
+    let mut range = OmahaRange::try_new(4).unwrap();
+    assert_eq!(range.try_add_hand(&cards("AsAhKs"), 1.0).unwrap_err(), "Hand must have exactly 4 cards, got 3");
+    assert_eq!(range.try_add_hand(&[51, 50, 49, 52], 1.0).unwrap_err(), "Invalid card 52, cards must be 0-51");
+    assert_eq!(range.try_add_hand(&[51, 50, 49, 51], 1.0).unwrap_err(), "Duplicate card As");
+    assert_eq!(
+        range.try_add_hand(&cards("AsAhKsKh"), -1.0).unwrap_err(),
+        "Weight must be a finite number >= 0, got -1"
+    );
+    assert!(range.is_empty());
+}
+
+#[test]
+#[allow(deprecated)]
+#[should_panic(expected = "Hand size must be 4, 5, or 6, got 7")]
+fn deprecated_new_panics_on_a_bad_size() {
+    OmahaRange::new(7);
+}
+
+#[test]
+#[allow(deprecated)]
+#[should_panic(expected = "Duplicate card As")]
+fn deprecated_add_hand_panics_on_a_bad_hand() {
+    OmahaRange::new(4).add_hand(&[51, 51, 50, 49], 1.0);
+}
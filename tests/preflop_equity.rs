@@ -0,0 +1,114 @@
+use std::sync::OnceLock;
+
+use poker_wasm::holdem::calculate_equity_vs_range;
+use poker_wasm::preflop::{preflop_matchup, PREFLOP_BOARDS};
+use poker_wasm::{cards_to_mask, parse_cards, HandEvaluator, HoldemRange, TwoPlusTwoEvaluator};
+
+fn evaluator() -> &'static TwoPlusTwoEvaluator {
+    static EVALUATOR: OnceLock<TwoPlusTwoEvaluator> = OnceLock::new();
+    EVALUATOR.get_or_init(TwoPlusTwoEvaluator::generated)
+}
+
+fn combo(text: &str) -> [u8; 2] {
+    let cards = parse_cards(text).unwrap();
+    [cards[0], cards[1]]
+}
+
+/// Win and tie counts by dealing every board
+fn enumerate(hero: [u8; 2], villain: [u8; 2]) -> [u32; 2] {
+    let evaluator = evaluator();
+    let dead = cards_to_mask(&hero) | cards_to_mask(&villain);
+    let deck: Vec<u8> = (0..52).filter(|&card| dead & (1u64 << card) == 0).collect();
+    let (mut wins, mut ties) = (0, 0);
+    let mut cards = [0u8; 7];
+    for a in 0..deck.len() {
+        for b in a + 1..deck.len() {
+            for c in b + 1..deck.len() {
+                for d in c + 1..deck.len() {
+                    for e in d + 1..deck.len() {
+                        cards[..5].copy_from_slice(&[deck[a], deck[b], deck[c], deck[d], deck[e]]);
+                        cards[5..].copy_from_slice(&hero);
+                        let hero_rank = evaluator.eval(&cards);
+                        cards[5..].copy_from_slice(&villain);
+                        let villain_rank = evaluator.eval(&cards);
+                        wins += (hero_rank > villain_rank) as u32;
+                        ties += (hero_rank == villain_rank) as u32;
+                    }
+                }
+            }
+        }
+    }
+    [wins, ties]
+}
+
+#[test]
+fn table_matches_enumeration() {
+    for (hero, villain) in [("AsAh", "KdKc"), ("7c2d", "Ah7h"), ("JsTs", "AcKs")] {
+        let (hero, villain) = (combo(hero), combo(villain));
+        assert_eq!(preflop_matchup(hero, villain), Some(enumerate(hero, villain)));
+    }
+}
+
+#[test]
+fn matchups_are_symmetric() {
+    let [wins, ties] = preflop_matchup(combo("AsAh"), combo("KdKc")).unwrap();
+    let [villain_wins, villain_ties] = preflop_matchup(combo("KdKc"), combo("AsAh")).unwrap();
+    assert_eq!(ties, villain_ties);
+    assert_eq!(wins + ties + villain_wins, PREFLOP_BOARDS);
+
+    // same suit pattern, relabeled
+    assert_eq!(preflop_matchup(combo("KhQh"), combo("Qc9d")), preflop_matchup(combo("KcQc"), combo("Qs9h")));
+    assert_eq!(preflop_matchup(combo("AsKs"), combo("AhKs")), None);
+}
+
+#[test]
+fn range_vs_range_sums_matchups() {
+    let hero: HoldemRange = "AKs".parse().unwrap();
+    let villain: HoldemRange = "QQ, AKo:0.5".parse().unwrap();
+    let results = calculate_equity_vs_range(evaluator(), &hero, &villain, &[], &[]).unwrap();
+    assert_eq!(results.len(), 4);
+
+    for result in &results {
+        let [c1, c2] = result.combo()[..] else { unreachable!() };
+        let (mut win, mut tie, mut total) = (0.0f64, 0.0f64, 0.0f64);
+        villain.for_each_weighted(|weight, idx| {
+            if let Some([wins, ties]) = preflop_matchup([c1, c2], HoldemRange::from_hand_idx(idx)) {
+                win += weight as f64 * wins as f64;
+                tie += weight as f64 * ties as f64;
+                total += weight as f64 * PREFLOP_BOARDS as f64;
+            }
+        });
+        let equity = result.equity();
+        // 6 QQ + 6 live AKo at half weight
+        assert_eq!(total, 9.0 * PREFLOP_BOARDS as f64);
+        assert!((equity.win() as f64 - win).abs() / total < 1e-6);
+        assert!((equity.tie() as f64 - tie).abs() / total < 1e-6);
+        assert!(((equity.win() + equity.tie() + equity.lose()) as f64 - total).abs() / total < 1e-6);
+    }
+}
+
+#[test]
+fn preflop_dead_cards_drop_combos() {
+    let hero: HoldemRange = "AsAh, AdAc".parse().unwrap();
+    let villain: HoldemRange = "KK".parse().unwrap();
+    let dead = parse_cards("AsKd").unwrap();
+    let results = calculate_equity_vs_range(evaluator(), &hero, &villain, &[], &dead).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].combo(), parse_cards("AcAd").unwrap());
+
+    // the three KK combos without the Kd, each over every board
+    let (mut win, mut tie) = (0u32, 0u32);
+    for villain in ["KsKh", "KsKc", "KhKc"] {
+        let [wins, ties] = enumerate(combo("AdAc"), combo(villain));
+        win += wins;
+        tie += ties;
+    }
+    let equity = results[0].equity();
+    let total = 3.0 * PREFLOP_BOARDS as f64;
+    assert!((equity.win() as f64 - win as f64).abs() / total < 1e-6);
+    assert!((equity.tie() as f64 - tie as f64).abs() / total < 1e-6);
+    assert!(((equity.win() + equity.tie() + equity.lose()) as f64 - total).abs() / total < 1e-6);
+
+    let err = calculate_equity_vs_range(evaluator(), &hero, &villain, &[], &[52]).unwrap_err();
+    assert_eq!(err, "Invalid card 52, cards must be 0-51");
+}
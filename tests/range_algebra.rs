@@ -0,0 +1,71 @@
+use poker_wasm::{parse_cards, HoldemRange, OmahaRange};
+
+fn holdem(text: &str) -> HoldemRange {
+    text.parse().unwrap()
+}
+
+fn omaha(text: &str) -> OmahaRange {
+    OmahaRange::parse(text, 4).unwrap()
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 1e-3, "{actual} != {expected}");
+}
+
+#[test]
+fn holdem_union_intersect_subtract() {
+    let open = holdem("22+, AJs+:0.5, KQs");
+    let three_bet = holdem("QQ+, AKs, KQs:0.25");
+
+    assert_eq!(open.union(&three_bet), holdem("22+, AQs:0.5, AJs:0.5, AKs, KQs"));
+    assert_eq!(open.intersect(&three_bet), holdem("QQ+, AKs:0.5, KQs:0.25"));
+    // never below 0, and subtracting a range from itself leaves nothing
+    assert_eq!(open.subtract(&three_bet), holdem("JJ-22, AQs:0.5, AJs:0.5, KQs:0.75"));
+    assert_eq!(open.subtract(&open), HoldemRange::new());
+    assert_eq!(three_bet.subtract(&open).total_weight(), 2.0);
+}
+
+#[test]
+fn holdem_weights() {
+    let range = holdem("AA, KK:0.5");
+    assert_eq!(range.total_weight(), 9.0);
+
+    assert_eq!(range.scale(0.5).unwrap(), holdem("AA:0.5, KK:0.25"));
+    assert_close(range.normalize(18.0).unwrap().total_weight(), 18.0);
+    assert_eq!(range.normalize(18.0).unwrap(), holdem("AA:2, KK"));
+    assert_eq!(range.clamp(0.0, 0.75).unwrap(), holdem("AA:0.75, KK:0.5"));
+    assert_eq!(range.mix(&holdem("QQ"), 0.5).unwrap(), holdem("AA:0.5, KK:0.25, QQ:0.5"));
+
+    assert_eq!(range.scale(-1.0).unwrap_err(), "Scale factor must be a finite number >= 0, got -1");
+    assert_eq!(HoldemRange::new().normalize(10.0).unwrap_err(), "Can't normalize an empty range");
+    assert_eq!(range.clamp(1.0, 0.5).unwrap_err(), "Clamp min (1) must not be above max (0.5)");
+    assert_eq!(range.mix(&range, 1.5).unwrap_err(), "Mix amount must be between 0 and 1, got 1.5");
+}
+
+#[test]
+fn omaha_algebra_matches_hands_in_any_order() {
+    let aces = omaha("AA**");
+    let kings = omaha("KK**");
+    let both = omaha("AAKK");
+
+    let union = aces.union(&kings).unwrap();
+    assert_eq!(union.len(), aces.len() + kings.len() - both.len());
+    assert_eq!(aces.intersect(&kings).unwrap(), both);
+    assert_eq!(aces.subtract(&kings).unwrap().len(), aces.len() - both.len());
+
+    // hands given in another order still match
+    let hand = parse_cards("KsAsKhAh").unwrap();
+    let one = OmahaRange::from_hands(4, [(hand.clone(), 0.5)]).unwrap();
+    let rest = both.subtract(&one).unwrap();
+    assert_eq!(rest.weight_of(&hand), Some(0.5));
+    assert_eq!(rest.len(), both.len());
+
+    let scaled = both.scale(0.5).unwrap().normalize(72.0).unwrap();
+    assert_close(scaled.total_weight(), 72.0);
+    assert!(scaled.iter().all(|(_, weight)| weight == 2.0));
+    assert_eq!(both.mix(&one, 1.0).unwrap(), one);
+    assert_eq!(both.scale(0.0).unwrap().len(), 0);
+
+    let plo5 = OmahaRange::parse("AA", 5).unwrap();
+    assert_eq!(aces.union(&plo5).unwrap_err(), "Range hand sizes must match (4 and 5)");
+}
@@ -0,0 +1,99 @@
+use poker_wasm::{parse_cards, HoldemRange, RangeFormat};
+
+fn sample(name: &str) -> String {
+    std::fs::read_to_string(format!("{}/tests/data/{name}", env!("CARGO_MANIFEST_DIR"))).unwrap()
+}
+
+fn weight(range: &HoldemRange, combo: &str) -> f32 {
+    let cards = parse_cards(combo).unwrap();
+    range.get_weight(HoldemRange::get_hand_idx([cards[0], cards[1]]))
+}
+
+fn round_trip(range: &HoldemRange, format: RangeFormat) -> HoldemRange {
+    let text = range.to_format(format).unwrap();
+    HoldemRange::from_format(&text, format).unwrap()
+}
+
+#[test]
+fn pio_sample_round_trips() {
+    let range = HoldemRange::from_format(&sample("pio_range.txt"), RangeFormat::Pio).unwrap();
+
+    assert_eq!(weight(&range, "AhAd"), 1.0);
+    assert_eq!(weight(&range, "JcJd"), 0.75);
+    assert_eq!(weight(&range, "AcJc"), 0.85);
+    assert_eq!(weight(&range, "AhJh"), 1.0);
+    assert_eq!(weight(&range, "AsTs"), 0.0);
+    assert_eq!(weight(&range, "KsJs"), 0.125);
+    assert_eq!(weight(&range, "KhQd"), 0.3);
+    assert_eq!(weight(&range, "8c8d"), 0.0);
+
+    assert_eq!(round_trip(&range, RangeFormat::Pio), range);
+    assert_eq!(round_trip(&range, RangeFormat::GtoPlus), range);
+}
+
+#[test]
+fn gto_plus_sample_round_trips() {
+    let range = HoldemRange::from_format(&sample("gtoplus_range.txt"), RangeFormat::GtoPlus).unwrap();
+
+    assert_eq!(weight(&range, "KsKh"), 1.0);
+    assert_eq!(weight(&range, "JsJh"), 0.75);
+    assert_eq!(weight(&range, "AhQc"), 0.5);
+    assert_eq!(weight(&range, "9s9h"), 0.333);
+    assert_eq!(weight(&range, "As2s"), 0.125);
+    assert_eq!(weight(&range, "AdQd"), 1.0);
+    assert_eq!(weight(&range, "AsKs"), 0.0);
+    assert_eq!(weight(&range, "AhKh"), 1.0);
+
+    assert_eq!(round_trip(&range, RangeFormat::GtoPlus), range);
+    assert_eq!(round_trip(&range, RangeFormat::Pio), range);
+}
+
+#[test]
+fn flopzilla_sample_round_trips() {
+    let range = HoldemRange::from_format(&sample("flopzilla_range.txt"), RangeFormat::Flopzilla).unwrap();
+
+    assert_eq!(weight(&range, "2c2d"), 1.0);
+    assert_eq!(weight(&range, "Kh9h"), 1.0);
+    assert_eq!(weight(&range, "Kh8h"), 0.0);
+    assert_eq!(weight(&range, "7h6h"), 1.0);
+    assert_eq!(weight(&range, "6c5d"), 0.0);
+    assert_eq!(weight(&range, "AcTd"), 1.0);
+
+    assert_eq!(round_trip(&range, RangeFormat::Flopzilla), range);
+    assert_eq!(round_trip(&range, RangeFormat::Pio), range);
+    assert_eq!(round_trip(&range, RangeFormat::GtoPlus), range);
+}
+
+#[test]
+fn flopzilla_rejects_weights() {
+    let range = HoldemRange::from_format(&sample("pio_range.txt"), RangeFormat::Pio).unwrap();
+    assert!(range.to_format(RangeFormat::Flopzilla).is_err());
+}
+
+#[test]
+fn reports_malformed_input() {
+    let err = HoldemRange::from_format("AA,[50]KK,QQ", RangeFormat::GtoPlus).unwrap_err();
+    assert_eq!(err.message, "Unclosed weight group");
+
+    let err = HoldemRange::from_format("AA,KK:x", RangeFormat::Pio).unwrap_err();
+    assert_eq!(err.position, 3);
+}
+
+#[test]
+fn gto_plus_checks_group_weights() {
+    for (text, message) in [
+        ("[-50]KK[/-50]", "Invalid weight '-50'"),
+        ("[NaN]KK[/NaN]", "Invalid weight 'NaN'"),
+        ("[inf]KK[/inf]", "Invalid weight 'inf'"),
+        ("[50]KK[/x]", "Invalid weight 'x'"),
+        ("[50]KK,QQ[/25]", "[/25] doesn't close the [50] group"),
+        ("KK[/50]", "Closing a weight group that wasn't opened"),
+    ] {
+        let err = HoldemRange::from_format(text, RangeFormat::GtoPlus).unwrap_err();
+        assert_eq!(err.message, message, "{text}");
+    }
+
+    // the closing tag may spell the same percentage differently
+    let range = HoldemRange::from_format("[50]KK,QQ[/50.0]", RangeFormat::GtoPlus).unwrap();
+    assert_eq!(range.get_weight(HoldemRange::get_hand_idx([45, 44])), 0.5);
+}
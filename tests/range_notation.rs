@@ -0,0 +1,104 @@
+use poker_wasm::{parse_cards, HoldemRange};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+fn set_combo(range: &mut HoldemRange, combo: &str, weight: f32) {
+    let cards = parse_cards(combo).unwrap();
+    range.set(HoldemRange::get_hand_idx([cards[0], cards[1]]), weight);
+}
+
+fn assert_round_trips(range: &HoldemRange) {
+    let text = range.to_string();
+    let parsed: HoldemRange = text.parse().unwrap_or_else(|err| panic!("{text}: {err}"));
+    assert_eq!(&parsed, range, "{text}");
+}
+
+#[test]
+fn display_round_trips() {
+    for text in [
+        "",
+        "22+",
+        "QQ-66, 33:0.5",
+        "A2s+, KTs-K7s, 76s+, ATo+, K9o:0.3",
+        "AK, QJ:0.75, T9s, T9o:0.25",
+        "TT+, AKs:0.1, AKo:0.2, 65s:0.333",
+    ] {
+        assert_round_trips(&text.parse().unwrap());
+    }
+
+    // a class at its most common weight with single combos overriding it
+    let mut range: HoldemRange = "TT+, A2+, KQo:0.5".parse().unwrap();
+    set_combo(&mut range, "AhKh", 0.25);
+    set_combo(&mut range, "AsKd", 0.0);
+    set_combo(&mut range, "7c6c", 0.6);
+    assert_eq!(range.to_string(), "TT+, A2+, KQo:0.5, AhKh:0.25, AsKd:0, 7c6c:0.6");
+    assert_round_trips(&range);
+}
+
+#[test]
+fn random_ranges_round_trip() {
+    let mut rng = StdRng::seed_from_u64(12);
+    let weights = [0.0, 0.0, 1.0, 1.0, 0.5, 0.25, 0.125, 1.0 / 3.0];
+    for _ in 0..200 {
+        let mut range = HoldemRange::new();
+        for idx in 0..1326 {
+            if rng.random_bool(0.3) {
+                range.set(idx, weights[rng.random_range(0..weights.len())]);
+            }
+        }
+        assert_round_trips(&range);
+    }
+}
+
+fn parse(text: &str) -> HoldemRange {
+    text.parse().unwrap_or_else(|err| panic!("{text}: {err}"))
+}
+
+fn combo_count(range: &HoldemRange) -> usize {
+    let mut count = 0;
+    range.for_each_weighted(|_, _| count += 1);
+    count
+}
+
+#[test]
+fn plus_and_span_expansions() {
+    // "+" raises the kicker up to the top card, so connectors stay put
+    assert_eq!(parse("ATo+"), parse("ATo, AJo, AQo, AKo"));
+    assert_eq!(combo_count(&parse("ATo+")), 4 * 12);
+    assert_eq!(parse("TT+"), parse("TT, JJ, QQ, KK, AA"));
+    assert_eq!(parse("KQs+"), parse("KQs"));
+    assert_eq!(parse("76s+"), parse("76s"));
+    assert_eq!(combo_count(&parse("KQo+")), 12);
+
+    assert_eq!(parse("A5s-A2s"), parse("A2s, A3s, A4s, A5s"));
+    // connectors climb with explicit spans
+    assert_eq!(parse("T9s-65s"), parse("65s, 76s, 87s, 98s, T9s"));
+    assert_eq!(parse("KQs-54s"), parse("54s, 65s, 76s, 87s, 98s, T9s, JTs, QJs, KQs"));
+    assert_eq!(combo_count(&parse("AKs-76s")), 8 * 4);
+    assert_eq!(parse("66-QQ"), parse("QQ-66"));
+    assert_eq!(combo_count(&parse("AK")), 16);
+    assert_eq!(combo_count(&parse("AhKh, AK:0")), 0);
+}
+
+#[test]
+fn errors_point_at_the_item() {
+    let error = |text: &str| text.parse::<HoldemRange>().unwrap_err();
+
+    let err = error("AA, KX");
+    assert_eq!((err.position, err.token.as_str(), err.message.as_str()), (4, "KX", "Invalid rank 'X'"));
+    assert_eq!(err.to_string(), "Invalid rank 'X' at position 4 ('KX')");
+
+    let err = error("AA,   QQ:abc");
+    assert_eq!((err.position, err.token.as_str(), err.message.as_str()), (6, "QQ:abc", "Invalid weight 'abc'"));
+
+    let err = error("22+, AKs-QQ");
+    assert_eq!((err.position, err.message.as_str()), (5, "Both ends of a span must have the same suitedness"));
+
+    assert_eq!(error("AKs, AhAh").message, "Combo uses the same card twice");
+    assert_eq!(error("AAs").message, "Pairs can't be suited or offsuit");
+    assert_eq!(error("AKx").message, "Expected 's' or 'o', got 'x'");
+    assert_eq!(error("AK9").message, "Expected 's' or 'o', got '9'");
+    assert_eq!(error("AKs-K9o").message, "Both ends of a span must have the same suitedness");
+    assert_eq!(error("AKs-T8s").message, "Span ends must share the top card or the gap between cards");
+    assert_eq!(error("AA:-1").message, "Invalid weight '-1'");
+}
@@ -0,0 +1,75 @@
+use std::sync::OnceLock;
+
+use poker_wasm::holdem::{calculate_equity_vs_range, calculate_leaf_equity};
+use poker_wasm::omaha::{calculate_omaha_equity_vs_range, calculate_omaha_leaf_equity};
+use poker_wasm::{cards_to_mask, parse_cards, HoldemRange, OmahaRange, TwoPlusTwoEvaluator};
+
+fn evaluator() -> &'static TwoPlusTwoEvaluator {
+    static EVALUATOR: OnceLock<TwoPlusTwoEvaluator> = OnceLock::new();
+    EVALUATOR.get_or_init(TwoPlusTwoEvaluator::generated)
+}
+
+/// Sum of leaf equity over every runout, without grouping runouts
+fn enumerate_flop(hero: &HoldemRange, villain: &HoldemRange, flop: &[u8], dead: &[u8]) -> Vec<(usize, [f64; 3])> {
+    let evaluator = evaluator();
+    let used = cards_to_mask(flop) | cards_to_mask(dead);
+    let mut totals = vec![[0.0f64; 3]; 1326];
+    for turn in 0..52u8 {
+        for river in turn + 1..52 {
+            if used & ((1u64 << turn) | (1u64 << river)) != 0 {
+                continue;
+            }
+            let board = [flop[0], flop[1], flop[2], turn, river];
+            for result in calculate_leaf_equity(evaluator, hero, villain, &board, dead).unwrap() {
+                let equity = result.equity();
+                let total = &mut totals[result.hand_idx()];
+                total[0] += equity.win() as f64;
+                total[1] += equity.tie() as f64;
+                total[2] += equity.lose() as f64;
+            }
+        }
+    }
+    (0..1326).filter(|&idx| hero.get_weight(idx) > 0.0).map(|idx| (idx, totals[idx])).collect()
+}
+
+fn assert_matches_enumeration(hero: &str, villain: &str, flop: &str, dead: &str) {
+    let (hero, villain): (HoldemRange, HoldemRange) = (hero.parse().unwrap(), villain.parse().unwrap());
+    let (flop, dead) = (parse_cards(flop).unwrap(), parse_cards(dead).unwrap());
+
+    let results = calculate_equity_vs_range(evaluator(), &hero, &villain, &flop, &dead).unwrap();
+    let expected = enumerate_flop(&hero, &villain, &flop, &dead);
+    assert_eq!(results.len(), expected.len());
+    for (result, (idx, [win, tie, lose])) in results.iter().zip(expected) {
+        assert_eq!(result.hand_idx(), idx);
+        let equity = result.equity();
+        let scale = win + tie + lose;
+        assert!((equity.win() as f64 - win).abs() <= scale * 1e-5);
+        assert!((equity.tie() as f64 - tie).abs() <= scale * 1e-5);
+        assert!((equity.lose() as f64 - lose).abs() <= scale * 1e-5);
+    }
+}
+
+#[test]
+fn holdem_symmetric_flops_match_enumeration() {
+    // monotone, two-tone, a range that breaks some symmetries, and dead cards
+    assert_matches_enumeration("TT+, AQs+, KQo", "99+, AK", "8h7h2h", "");
+    assert_matches_enumeration("TT+, AQs+, KQo", "99+, AK", "8h7d2h", "");
+    assert_matches_enumeration("TT+, AQs+, KQo, AsJs", "99+, AK", "8h7h2h", "");
+    assert_matches_enumeration("TT+, AQs+, KQo", "99+, AK", "8h7h2h", "3c");
+}
+
+#[test]
+fn omaha_symmetric_turn_matches_leaf_equity() {
+    let evaluator = evaluator();
+    let range = OmahaRange::parse("AA**, KKQQ", 4).unwrap();
+    // suits s and c can swap without changing the board, hero or range
+    let hero = parse_cards("AsAcKsKc").unwrap();
+    let board = parse_cards("9h8h2h3d").unwrap();
+
+    let runouts = calculate_omaha_equity_vs_range(evaluator, &hero, &range, &board, &[]).unwrap();
+    assert_eq!(runouts.len(), 44);
+    for runout in runouts {
+        let full_board: [u8; 5] = runout.board().try_into().unwrap();
+        assert_eq!(runout, calculate_omaha_leaf_equity(evaluator, &hero, &range, &full_board, &[]).unwrap());
+    }
+}
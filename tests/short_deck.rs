@@ -0,0 +1,119 @@
+use poker_wasm::short_deck::{calculate_short_deck_equity_vs_range, calculate_short_deck_leaf_equity};
+use poker_wasm::{parse_cards, HandCategory, HandEvaluator, HoldemRange, ShortDeckEvaluator, ShortDeckRange};
+
+fn eval(hand: &str) -> i32 {
+    ShortDeckEvaluator.eval(&parse_cards(hand).unwrap())
+}
+
+fn category(hand: &str) -> HandCategory {
+    HandCategory::from_short_deck_rank(eval(hand)).unwrap()
+}
+
+fn range(notation: &str) -> ShortDeckRange {
+    ShortDeckRange::try_from(notation.parse::<HoldemRange>().unwrap()).unwrap()
+}
+
+#[test]
+fn ace_six_straight_is_the_lowest() {
+    assert_eq!(category("As6c7d8h9s"), HandCategory::Straight);
+    assert!(eval("As6c7d8h9s") < eval("6s7c8d9hTs"));
+    assert!(eval("As6c7d8h9s") > eval("AsAcAd8h9s"));
+    // without the deuce through five the wheel is just ace high
+    assert_eq!(category("As6c7d8hTs"), HandCategory::HighCard);
+    assert_eq!(category("As6s7s8s9s"), HandCategory::StraightFlush);
+}
+
+#[test]
+fn flush_beats_full_house() {
+    assert_eq!(category("As9s7s8sJs"), HandCategory::Flush);
+    assert_eq!(category("AsAcAdKhKs"), HandCategory::FullHouse);
+    assert!(eval("6s7s9sTsQs") > eval("AsAcAdKhKs"));
+    assert!(eval("6s7s9sTsQs") < eval("6c6d6h6sAs"));
+}
+
+#[test]
+fn ranges_only_hold_short_deck_combos() {
+    let mut combos = ShortDeckRange::new();
+    assert!(combos.set_hand(&parse_cards("AsKs").unwrap(), 1.0).is_ok());
+    assert!(combos.set_hand(&parse_cards("As5s").unwrap(), 1.0).is_err());
+    assert!(combos.set_hand(&[51, 51], 1.0).is_err());
+    assert!(combos.set(HoldemRange::get_hand_idx([51, 0]), 1.0).is_err());
+
+    assert_eq!(range("66+").as_holdem_range(), &"66+".parse::<HoldemRange>().unwrap());
+    assert!(ShortDeckRange::try_from("55+".parse::<HoldemRange>().unwrap()).is_err());
+    let valid = (0..1326).filter(|&idx| ShortDeckRange::is_valid_hand_idx(idx)).count();
+    assert_eq!(valid, 36 * 35 / 2);
+}
+
+#[test]
+fn boards_must_be_short_deck_cards() {
+    let (hero, villain) = (range("AA"), range("KK"));
+    let river = parse_cards("Qh7d6h9cTs").unwrap();
+    let results = calculate_short_deck_leaf_equity(&hero, &villain, &river, &[]).unwrap();
+    assert_eq!(results.len(), 6);
+
+    let with_five = parse_cards("Qh7d6h9c5s").unwrap();
+    assert_eq!(
+        calculate_short_deck_leaf_equity(&hero, &villain, &with_five, &[]).unwrap_err(),
+        "Card 15 is not in the Short Deck"
+    );
+    assert_eq!(
+        calculate_short_deck_leaf_equity(&hero, &villain, &river[..3], &[]).unwrap_err(),
+        "Board must be exactly 5 cards"
+    );
+    assert_eq!(
+        calculate_short_deck_leaf_equity(&hero, &villain, &[42, 42, 20, 38, 35], &[]).unwrap_err(),
+        "Duplicate card Qh"
+    );
+    assert_eq!(
+        calculate_short_deck_equity_vs_range(&hero, &villain, &parse_cards("Qh7d2c").unwrap(), &[]).unwrap_err(),
+        "Card 0 is not in the Short Deck"
+    );
+}
+
+#[test]
+fn dead_cards_never_come_and_block_combos() {
+    let (hero, villain) = (range("AA"), range("KK, QQ"));
+    let flop = parse_cards("Jh7d6h").unwrap();
+    let dead = parse_cards("Ks").unwrap();
+
+    // enumerating by hand over the 36 - 3 - 1 cards left matches
+    let results = calculate_short_deck_equity_vs_range(&hero, &villain, &flop, &dead).unwrap();
+    let deck: Vec<u8> = (16..52).filter(|card| !flop.contains(card) && !dead.contains(card)).collect();
+    for result in &results {
+        let (mut win, mut lose) = (0.0, 0.0);
+        for (i, &turn) in deck.iter().enumerate() {
+            for &river in &deck[i + 1..] {
+                let board = [&flop[..], &[turn, river]].concat();
+                if result.combo().iter().any(|card| board.contains(card)) {
+                    continue;
+                }
+                let leaf = calculate_short_deck_leaf_equity(&hero, &villain, &board, &dead).unwrap();
+                let equity = leaf.iter().find(|leaf| leaf.hand_idx() == result.hand_idx()).unwrap().equity();
+                win += equity.win();
+                lose += equity.lose();
+            }
+        }
+        assert!((result.equity().win() - win).abs() < 1e-2, "{} != {win}", result.equity().win());
+        assert!((result.equity().lose() - lose).abs() < 1e-2, "{} != {lose}", result.equity().lose());
+    }
+
+    // villain's kings holding the dead card are gone
+    let river = parse_cards("Jh7d6h9cTs").unwrap();
+    let leaf = calculate_short_deck_leaf_equity(&hero, &villain, &river, &dead).unwrap();
+    let equity = leaf[0].equity();
+    assert_eq!(equity.win() + equity.tie() + equity.lose(), 3.0 + 6.0);
+
+    assert_eq!(
+        calculate_short_deck_leaf_equity(&hero, &villain, &river, &parse_cards("Jh").unwrap()).unwrap_err(),
+        "Dead cards can't be on the board"
+    );
+    assert_eq!(
+        calculate_short_deck_equity_vs_range(&hero, &villain, &flop, &parse_cards("2c").unwrap()).unwrap_err(),
+        "Card 0 is not in the Short Deck"
+    );
+    assert_eq!(
+        calculate_short_deck_equity_vs_range(&hero, &villain, &flop, &[60]).unwrap_err(),
+        "Invalid card 60, cards must be 0-51"
+    );
+}
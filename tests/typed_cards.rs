@@ -0,0 +1,96 @@
+use poker_wasm::holdem::{calculate_leaf_equity, calculate_leaf_equity_typed, hand_leaf_equity_vs_range_typed};
+use poker_wasm::omaha::calculate_omaha_equity_monte_carlo_flop_typed;
+use poker_wasm::{
+    card_to_string, Board, Card, CardError, EquityError, HoldemHand, HoldemRange, OmahaHand, OmahaRange,
+    TwoPlusTwoEvaluator,
+};
+
+fn card(text: &str) -> Card {
+    text.parse().unwrap()
+}
+
+#[test]
+fn parse_and_display() {
+    let ace = card("As");
+    assert_eq!((ace.value(), ace.rank(), ace.suit()), (51, 12, 3));
+    assert_eq!(ace.to_string(), "As");
+    assert_eq!(card(" tD ").to_string(), "Td");
+    assert_eq!(card_to_string(51), "As");
+    assert_eq!(card_to_string(52), "??");
+    assert_eq!(Card::from_rank_suit(0, 0).unwrap(), card("2c"));
+    assert_eq!(Card::try_from(51u8).unwrap(), ace);
+
+    let hand: HoldemHand = "Ah Kd".parse().unwrap();
+    assert_eq!(hand.to_string(), "AhKd");
+    assert_eq!(hand.cards(), [card("Ah"), card("Kd")]);
+    assert_eq!(hand.hand_idx(), HoldemRange::get_hand_idx(*hand.as_bytes()));
+
+    let omaha: OmahaHand = "ah,kd,7c,2s,5h".parse().unwrap();
+    assert_eq!(omaha.to_string(), "AhKd7c2s5h");
+    assert_eq!(omaha.cards().len(), 5);
+
+    let board: Board = "Qh7d2h".parse().unwrap();
+    assert_eq!(board.to_string(), "Qh7d2h");
+    assert_eq!(board.as_ref(), &[42, 21, 2]);
+    assert_eq!(Board::new(&board.cards()).unwrap(), board);
+}
+
+#[test]
+fn card_errors() {
+    assert_eq!(Card::new(52), Err(CardError::OutOfRange(52)));
+    assert_eq!(Card::new(52).unwrap_err().to_string(), "Invalid card 52, cards must be 0-51");
+    assert_eq!("Ax".parse::<Card>(), Err(CardError::Invalid("Invalid card 'Ax'".to_string())));
+    assert_eq!("AsK".parse::<Card>().unwrap_err().to_string(), "Invalid card 'AsK'");
+    assert!(Card::from_rank_suit(13, 0).is_err());
+
+    assert_eq!("AsAs".parse::<HoldemHand>(), Err(CardError::Duplicate(card("As"))));
+    assert_eq!("AsAs".parse::<HoldemHand>().unwrap_err().to_string(), "Duplicate card As");
+    assert_eq!(
+        "AsKsQs".parse::<HoldemHand>(),
+        Err(CardError::WrongCount { expected: "2", actual: 3 })
+    );
+    assert_eq!(
+        OmahaHand::from_bytes(&[0, 1, 2]).unwrap_err().to_string(),
+        "Expected 4, 5, or 6 cards, got 3"
+    );
+    assert_eq!(OmahaHand::from_bytes(&[0, 1, 2, 60]), Err(CardError::OutOfRange(60)));
+    assert_eq!(
+        "AsKsQsJsTs9s".parse::<Board>(),
+        Err(CardError::WrongCount { expected: "3, 4, or 5", actual: 6 })
+    );
+    assert!(matches!("Qh7dZz".parse::<Board>(), Err(CardError::Invalid(_))));
+
+    let board: Board = "Qh7d2h".parse().unwrap();
+    assert_eq!(board.check_hand(&[51, 42]), Err(CardError::Duplicate(card("Qh"))));
+    assert_eq!(board.check_hand(&[51, 50]), Ok(()));
+}
+
+#[test]
+fn typed_leaf_equity_matches_bytes() {
+    let evaluator = TwoPlusTwoEvaluator::generated();
+    let hero: HoldemRange = "TT+, AQs+".parse().unwrap();
+    let villain: HoldemRange = "99+, AK".parse().unwrap();
+    let board: Board = "Qh7d2h5c".parse().unwrap();
+
+    let typed = calculate_leaf_equity_typed(&evaluator, &hero, &villain, &board, &[card("Ks")]).unwrap();
+    let bytes = calculate_leaf_equity(&evaluator, &hero, &villain, board.as_bytes(), &[card("Ks").value()]).unwrap();
+    assert_eq!(typed, bytes);
+
+    assert_eq!(
+        calculate_leaf_equity_typed(&evaluator, &hero, &villain, &board, &[card("Qh")]).unwrap_err(),
+        EquityError::Invalid("Dead cards can't be on the board".to_string())
+    );
+
+    // card clashes come back as the CardError
+    let hand: HoldemHand = "QhJh".parse().unwrap();
+    let err = hand_leaf_equity_vs_range_typed(&evaluator, &hand, &villain, &board, &[]).unwrap_err();
+    assert_eq!(err, EquityError::Card(CardError::Duplicate(card("Qh"))));
+    assert_eq!(err.to_string(), "Duplicate card Qh");
+
+    let omaha: OmahaHand = "AsKsJcTc".parse().unwrap();
+    let range = OmahaRange::parse("AA**", 4).unwrap();
+    assert_eq!(
+        calculate_omaha_equity_monte_carlo_flop_typed(&evaluator, &omaha, &range, &board, 10, &[]).unwrap_err(),
+        EquityError::Card(CardError::WrongCount { expected: "3", actual: 4 })
+    );
+}